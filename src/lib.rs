@@ -21,4 +21,4 @@ pub mod os;
 mod sys_common;
 mod sys;
 
-mod net;
\ No newline at end of file
+pub mod net;
\ No newline at end of file
@@ -47,16 +47,17 @@ pub use self::ip::{IpAddr, Ipv4Addr, Ipv6Addr, Ipv6MulticastScope};
 // #[stable(feature = "rust1", since = "1.0.0")]
 pub use self::addr::{SocketAddr, SocketAddrV4, SocketAddrV6, ToSocketAddrs};
 // #[stable(feature = "rust1", since = "1.0.0")]
-// pub use self::tcp::{TcpStream, TcpListener, Incoming};
+pub use self::tcp::{TcpStream, TcpListener, TcpBuilder, Incoming};
+pub use self::tcp::{HappyEyeballsOpts, KeepaliveParams, TcpInfo};
 // #[stable(feature = "rust1", since = "1.0.0")]
-// pub use self::udp::UdpSocket;
+pub use self::udp::{UdpSocket, UdpBuilder, PacketInfo, UdpEvent};
 // #[stable(feature = "rust1", since = "1.0.0")]
 pub use self::parser::AddrParseError;
 
 mod ip;
 mod addr;
-// mod tcp;
-// mod udp;
+mod tcp;
+mod udp;
 mod parser;
 #[cfg(test)]
 mod test;
@@ -97,6 +98,67 @@ pub enum Shutdown {
     Both,
 }
 
+/// A Differentiated Services codepoint, as defined in [IETF RFC 2474].
+///
+/// The codepoint makes up the upper six bits of the IPv4 type-of-service byte
+/// and of the IPv6 traffic class, which are set with `set_tos_v4` and
+/// `set_tclass_v6` on [`TcpStream`], [`TcpListener`] and [`UdpSocket`].
+///
+/// Windows ignores the type-of-service byte set on a socket unless the
+/// `DisableUserTOSSetting` registry value is cleared; marking is otherwise
+/// left to the QoS APIs and group policy.
+///
+/// [IETF RFC 2474]: https://tools.ietf.org/html/rfc2474
+/// [`TcpStream`]: struct.TcpStream.html
+/// [`TcpListener`]: struct.TcpListener.html
+/// [`UdpSocket`]: struct.UdpSocket.html
+///
+/// # Examples
+///
+/// ```ignore
+/// use std::net::Dscp;
+///
+/// assert_eq!(Dscp::new(46), Some(Dscp::EF));
+/// assert_eq!(Dscp::EF.to_tos(), 0xb8);
+/// assert_eq!(Dscp::new(64), None);
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Dscp(u8);
+
+impl Dscp {
+    /// The default, best-effort codepoint.
+    pub const DEFAULT: Dscp = Dscp(0);
+    /// The expedited forwarding codepoint, for low-latency traffic such as
+    /// voice.
+    pub const EF: Dscp = Dscp(46);
+
+    /// Creates a codepoint from its six-bit value, or returns `None` if
+    /// `value` doesn't fit in six bits.
+    pub fn new(value: u8) -> Option<Dscp> {
+        if value < 64 {
+            Some(Dscp(value))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the six-bit value of the codepoint.
+    pub fn value(&self) -> u8 {
+        self.0
+    }
+
+    /// Returns the type-of-service or traffic class byte carrying this
+    /// codepoint, with the ECN bits cleared.
+    pub fn to_tos(&self) -> u32 {
+        (self.0 as u32) << 2
+    }
+
+    /// Extracts the codepoint from a type-of-service or traffic class byte.
+    pub fn from_tos(tos: u32) -> Dscp {
+        Dscp(((tos >> 2) & 0x3f) as u8)
+    }
+}
+
 #[doc(hidden)]
 trait NetInt {
     fn from_be(i: Self) -> Self;
@@ -0,0 +1,2461 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::io::prelude::*;
+
+use std::fmt;
+use std::io::{self, Initializer};
+use net::{ToSocketAddrs, SocketAddr, Shutdown, Dscp};
+use sys_common::net as net_imp;
+use sys_common::{AsInner, FromInner, IntoInner};
+use std::time::Duration;
+
+/// A TCP stream between a local and a remote socket.
+///
+/// After creating a `TcpStream` by either [`connect`]ing to a remote host or
+/// [`accept`]ing a connection on a [`TcpListener`], data can be transmitted
+/// by [reading] and [writing] to it.
+///
+/// The connection will be closed when the value is dropped. The reading and writing
+/// portions of the connection can also be shut down individually with the [`shutdown`]
+/// method.
+///
+/// The Transmission Control Protocol is specified in [IETF RFC 793].
+///
+/// [`accept`]: ../../std/net/struct.TcpListener.html#method.accept
+/// [`connect`]: #method.connect
+/// [IETF RFC 793]: https://tools.ietf.org/html/rfc793
+/// [reading]: ../../std/io/trait.Read.html
+/// [`shutdown`]: #method.shutdown
+/// [`TcpListener`]: ../../std/net/struct.TcpListener.html
+/// [writing]: ../../std/io/trait.Write.html
+///
+/// # Examples
+///
+/// ```ignore
+/// use std::io::prelude::*;
+/// use std::net::TcpStream;
+///
+/// {
+///     let mut stream = TcpStream::connect("127.0.0.1:34254").unwrap();
+///
+///     // ignore the Result
+///     let _ = stream.write(&[1]);
+///     let _ = stream.read(&mut [0; 128]); // ignore here too
+/// } // the stream is closed here
+/// ```
+// #[stable(feature = "rust1", since = "1.0.0")]
+pub struct TcpStream(net_imp::TcpStream);
+
+/// A TCP socket server, listening for connections.
+///
+/// After creating a `TcpListener` by [`bind`]ing it to a socket address, it listens
+/// for incoming TCP connections. These can be accepted by calling [`accept`] or by
+/// iterating over the [`Incoming`] iterator returned by [`incoming`][`TcpListener::incoming`].
+///
+/// The socket will be closed when the value is dropped.
+///
+/// The Transmission Control Protocol is specified in [IETF RFC 793].
+///
+/// [`accept`]: #method.accept
+/// [`bind`]: #method.bind
+/// [IETF RFC 793]: https://tools.ietf.org/html/rfc793
+/// [`Incoming`]: ../../std/net/struct.Incoming.html
+/// [`TcpListener::incoming`]: #method.incoming
+///
+/// # Examples
+///
+/// ```ignore
+/// use std::net::{TcpListener, TcpStream};
+///
+/// fn handle_client(stream: TcpStream) {
+///     // ...
+/// }
+///
+/// let listener = TcpListener::bind("127.0.0.1:80").unwrap();
+///
+/// // accept connections and process them serially
+/// for stream in listener.incoming() {
+///     handle_client(stream.unwrap());
+/// }
+/// ```
+// #[stable(feature = "rust1", since = "1.0.0")]
+pub struct TcpListener(net_imp::TcpListener);
+
+/// An iterator that infinitely [`accept`]s connections on a [`TcpListener`].
+///
+/// This `struct` is created by the [`incoming`] method on [`TcpListener`].
+/// See its documentation for more.
+///
+/// [`accept`]: ../../std/net/struct.TcpListener.html#method.accept
+/// [`incoming`]: ../../std/net/struct.TcpListener.html#method.incoming
+/// [`TcpListener`]: ../../std/net/struct.TcpListener.html
+// #[stable(feature = "rust1", since = "1.0.0")]
+#[derive(Debug)]
+pub struct Incoming<'a> { listener: &'a TcpListener }
+
+/// Settings for [`TcpStream::connect_happy_eyeballs`].
+///
+/// [`TcpStream::connect_happy_eyeballs`]: struct.TcpStream.html#method.connect_happy_eyeballs
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct HappyEyeballsOpts {
+    /// How long to wait for a connection attempt before starting the next
+    /// one alongside it. The default is 250 milliseconds, as recommended by
+    /// RFC 8305.
+    pub attempt_delay: Duration,
+    /// How long to wait for any of the attempts to succeed, or `None` to wait
+    /// until every one of them has failed. The default is `None`.
+    pub timeout: Option<Duration>,
+}
+
+impl Default for HappyEyeballsOpts {
+    fn default() -> HappyEyeballsOpts {
+        HappyEyeballsOpts {
+            attempt_delay: Duration::from_millis(250),
+            timeout: None,
+        }
+    }
+}
+
+/// Keepalive settings for a [`TcpStream`], passed to
+/// [`set_keepalive`](struct.TcpStream.html#method.set_keepalive).
+///
+/// Once a connection has been idle for `idle`, a keepalive probe is sent every
+/// `interval` until the peer answers; after `count` unanswered probes the
+/// connection is dropped.
+///
+/// [`TcpStream`]: struct.TcpStream.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct KeepaliveParams {
+    /// How long the connection must be idle before the first probe is sent.
+    pub idle: Duration,
+    /// How long to wait between unanswered probes.
+    pub interval: Duration,
+    /// How many unanswered probes are sent before the connection is dropped.
+    pub count: u32,
+}
+
+/// Statistics about a TCP connection, as returned by
+/// [`tcp_info`](struct.TcpStream.html#method.tcp_info).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TcpInfo {
+    /// The smoothed round-trip time.
+    pub rtt: Duration,
+    /// The variance of the round-trip time, if the platform tracks it.
+    pub rtt_var: Option<Duration>,
+    /// How many bytes have been retransmitted on the connection.
+    pub bytes_retransmitted: u32,
+    /// The congestion window, in bytes.
+    pub cwnd: u32,
+    /// The maximum segment size, in bytes.
+    pub mss: u32,
+    /// How many bytes have been sent but not yet acknowledged.
+    pub bytes_in_flight: u32,
+}
+
+impl TcpStream {
+    /// Opens a TCP connection to a remote host.
+    ///
+    /// `addr` is an address of the remote host. Anything which implements
+    /// [`ToSocketAddrs`] trait can be supplied for the address; see this trait
+    /// documentation for concrete examples.
+    ///
+    /// If `addr` yields multiple addresses, `connect` will be attempted with
+    /// each of the addresses until a connection is successful. If none of
+    /// the addresses result in a successful connection, the error returned from
+    /// the last connection attempt (the last address) is returned.
+    ///
+    /// [`ToSocketAddrs`]: ../../std/net/trait.ToSocketAddrs.html
+    ///
+    /// # Examples
+    ///
+    /// Open a TCP connection to `127.0.0.1:8080`:
+    ///
+    /// ```ignore
+    /// use std::net::TcpStream;
+    ///
+    /// if let Ok(stream) = TcpStream::connect("127.0.0.1:8080") {
+    ///     println!("Connected to the server!");
+    /// } else {
+    ///     println!("Couldn't connect to server...");
+    /// }
+    /// ```
+    // #[stable(feature = "rust1", since = "1.0.0")]
+    pub fn connect<A: ToSocketAddrs>(addr: A) -> io::Result<TcpStream> {
+        super::each_addr(addr, net_imp::TcpStream::connect).map(TcpStream)
+    }
+
+    /// Opens a TCP connection to a remote host, racing connection attempts to
+    /// the addresses `addr` yields as described by RFC 8305 ("Happy
+    /// Eyeballs").
+    ///
+    /// The addresses are reordered to alternate between IPv6 and IPv4,
+    /// starting with the family of the first one. A connection attempt is
+    /// started to each address in turn, either once the previous attempt has
+    /// failed or once [`attempt_delay`] has passed without it succeeding, and
+    /// the first attempt to succeed wins. So an address that can't be reached
+    /// delays the connection by `attempt_delay` rather than by a full connect
+    /// timeout.
+    ///
+    /// If every attempt fails, the error of the last one to fail is returned.
+    /// If [`timeout`] passes first, an error with kind `TimedOut` is returned.
+    ///
+    /// [`attempt_delay`]: struct.HappyEyeballsOpts.html#structfield.attempt_delay
+    /// [`timeout`]: struct.HappyEyeballsOpts.html#structfield.timeout
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::{HappyEyeballsOpts, TcpStream};
+    ///
+    /// let stream = TcpStream::connect_happy_eyeballs("example.com:80",
+    ///                                                &HappyEyeballsOpts::default())
+    ///                        .expect("Couldn't connect to the server...");
+    /// ```
+    pub fn connect_happy_eyeballs<A: ToSocketAddrs>(addr: A, opts: &HappyEyeballsOpts)
+                                                    -> io::Result<TcpStream> {
+        let addrs: Vec<SocketAddr> = addr.to_socket_addrs()?.collect();
+        net_imp::TcpStream::connect_happy_eyeballs(&addrs, opts.attempt_delay, opts.timeout)
+            .map(TcpStream)
+    }
+
+    /// Opens a TCP connection to a remote host with a timeout.
+    ///
+    /// Unlike `connect`, `connect_timeout` takes a single [`SocketAddr`] since
+    /// timeout must be applied to individual addresses.
+    ///
+    /// It is an error to pass a zero `Duration` to this function.
+    ///
+    /// Unlike other methods on `TcpStream`, this does not correspond to a
+    /// single system call. It instead calls `connect` in nonblocking mode and
+    /// then uses an OS-specific mechanism to await the completion of the
+    /// connection request.
+    ///
+    /// [`SocketAddr`]: ../../std/net/enum.SocketAddr.html
+    // #[stable(feature = "tcpstream_connect_timeout", since = "1.21.0")]
+    pub fn connect_timeout(addr: &SocketAddr, timeout: Duration) -> io::Result<TcpStream> {
+        net_imp::TcpStream::connect_timeout(addr, timeout).map(TcpStream)
+    }
+
+    /// Returns the socket address of the remote peer of this TCP connection.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4, TcpStream};
+    ///
+    /// let stream = TcpStream::connect("127.0.0.1:8080")
+    ///                        .expect("Couldn't connect to the server...");
+    /// assert_eq!(stream.peer_addr().unwrap(),
+    ///            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(127, 0, 0, 1), 8080)));
+    /// ```
+    // #[stable(feature = "rust1", since = "1.0.0")]
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.0.peer_addr()
+    }
+
+    /// Returns the socket address of the local half of this TCP connection.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::{IpAddr, Ipv4Addr, TcpStream};
+    ///
+    /// let stream = TcpStream::connect("127.0.0.1:8080")
+    ///                        .expect("Couldn't connect to the server...");
+    /// assert_eq!(stream.local_addr().unwrap().ip(),
+    ///            IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)));
+    /// ```
+    // #[stable(feature = "rust1", since = "1.0.0")]
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.0.socket_addr()
+    }
+
+    /// Shuts down the read, write, or both halves of this connection.
+    ///
+    /// This function will cause all pending and future I/O on the specified
+    /// portions to return immediately with an appropriate value (see the
+    /// documentation of [`Shutdown`]).
+    ///
+    /// [`Shutdown`]: ../../std/net/enum.Shutdown.html
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// Calling this function multiple times may result in different behavior,
+    /// depending on the operating system. On Linux, the second call will
+    /// return `Ok(())`, but on macOS, it will return `ErrorKind::NotConnected`.
+    /// This may change in the future.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::{Shutdown, TcpStream};
+    ///
+    /// let stream = TcpStream::connect("127.0.0.1:8080")
+    ///                        .expect("Couldn't connect to the server...");
+    /// stream.shutdown(Shutdown::Both).expect("shutdown call failed");
+    /// ```
+    // #[stable(feature = "rust1", since = "1.0.0")]
+    pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
+        self.0.shutdown(how)
+    }
+
+    /// Creates a new independently owned handle to the underlying socket.
+    ///
+    /// The returned `TcpStream` is a reference to the same stream that this
+    /// object references. Both handles will read and write the same stream of
+    /// data, and options set on one stream will be propagated to the other
+    /// stream.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::TcpStream;
+    ///
+    /// let stream = TcpStream::connect("127.0.0.1:8080")
+    ///                        .expect("Couldn't connect to the server...");
+    /// let stream_clone = stream.try_clone().expect("clone failed...");
+    /// ```
+    // #[stable(feature = "rust1", since = "1.0.0")]
+    pub fn try_clone(&self) -> io::Result<TcpStream> {
+        self.0.duplicate().map(TcpStream)
+    }
+
+    /// Sets the read timeout to the timeout specified.
+    ///
+    /// If the value specified is [`None`], then [`read`] calls will block
+    /// indefinitely. An [`Err`] is returned if the zero [`Duration`] is
+    /// passed to this method.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// Platforms may return a different error code whenever a read times out as
+    /// a result of setting this option. For example Unix typically returns an
+    /// error of the kind [`WouldBlock`], but Windows may return [`TimedOut`].
+    ///
+    /// [`None`]: ../../std/option/enum.Option.html#variant.None
+    /// [`Err`]: ../../std/result/enum.Result.html#variant.Err
+    /// [`read`]: ../../std/io/trait.Read.html#tymethod.read
+    /// [`WouldBlock`]: ../../std/io/enum.ErrorKind.html#variant.WouldBlock
+    /// [`TimedOut`]: ../../std/io/enum.ErrorKind.html#variant.TimedOut
+    /// [`Duration`]: ../../std/time/struct.Duration.html
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::TcpStream;
+    ///
+    /// let stream = TcpStream::connect("127.0.0.1:8080")
+    ///                        .expect("Couldn't connect to the server...");
+    /// stream.set_read_timeout(None).expect("set_read_timeout call failed");
+    /// ```
+    // #[stable(feature = "socket_timeout", since = "1.4.0")]
+    pub fn set_read_timeout(&self, dur: Option<Duration>) -> io::Result<()> {
+        self.0.set_read_timeout(dur)
+    }
+
+    /// Sets the write timeout to the timeout specified.
+    ///
+    /// If the value specified is [`None`], then [`write`] calls will block
+    /// indefinitely. An [`Err`] is returned if the zero [`Duration`] is
+    /// passed to this method.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// Platforms may return a different error code whenever a write times out
+    /// as a result of setting this option. For example Unix typically returns
+    /// an error of the kind [`WouldBlock`], but Windows may return [`TimedOut`].
+    ///
+    /// [`None`]: ../../std/option/enum.Option.html#variant.None
+    /// [`Err`]: ../../std/result/enum.Result.html#variant.Err
+    /// [`write`]: ../../std/io/trait.Write.html#tymethod.write
+    /// [`Duration`]: ../../std/time/struct.Duration.html
+    /// [`WouldBlock`]: ../../std/io/enum.ErrorKind.html#variant.WouldBlock
+    /// [`TimedOut`]: ../../std/io/enum.ErrorKind.html#variant.TimedOut
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::TcpStream;
+    ///
+    /// let stream = TcpStream::connect("127.0.0.1:8080")
+    ///                        .expect("Couldn't connect to the server...");
+    /// stream.set_write_timeout(None).expect("set_write_timeout call failed");
+    /// ```
+    // #[stable(feature = "socket_timeout", since = "1.4.0")]
+    pub fn set_write_timeout(&self, dur: Option<Duration>) -> io::Result<()> {
+        self.0.set_write_timeout(dur)
+    }
+
+    /// Returns the read timeout of this socket.
+    ///
+    /// If the timeout is [`None`], then [`read`] calls will block indefinitely.
+    ///
+    /// [`None`]: ../../std/option/enum.Option.html#variant.None
+    /// [`read`]: ../../std/io/trait.Read.html#tymethod.read
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::TcpStream;
+    ///
+    /// let stream = TcpStream::connect("127.0.0.1:8080")
+    ///                        .expect("Couldn't connect to the server...");
+    /// stream.set_read_timeout(None).expect("set_read_timeout call failed");
+    /// assert_eq!(stream.read_timeout().unwrap(), None);
+    /// ```
+    // #[stable(feature = "socket_timeout", since = "1.4.0")]
+    pub fn read_timeout(&self) -> io::Result<Option<Duration>> {
+        self.0.read_timeout()
+    }
+
+    /// Returns the write timeout of this socket.
+    ///
+    /// If the timeout is [`None`], then [`write`] calls will block indefinitely.
+    ///
+    /// [`None`]: ../../std/option/enum.Option.html#variant.None
+    /// [`write`]: ../../std/io/trait.Write.html#tymethod.write
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::TcpStream;
+    ///
+    /// let stream = TcpStream::connect("127.0.0.1:8080")
+    ///                        .expect("Couldn't connect to the server...");
+    /// stream.set_write_timeout(None).expect("set_write_timeout call failed");
+    /// assert_eq!(stream.write_timeout().unwrap(), None);
+    /// ```
+    // #[stable(feature = "socket_timeout", since = "1.4.0")]
+    pub fn write_timeout(&self) -> io::Result<Option<Duration>> {
+        self.0.write_timeout()
+    }
+
+    /// Receives data on the socket from the remote address to which it is
+    /// connected, without removing that data from the queue. On success,
+    /// returns the number of bytes peeked.
+    ///
+    /// Successive calls return the same data. This is accomplished by passing
+    /// `MSG_PEEK` as a flag to the underlying `recv` system call.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::TcpStream;
+    ///
+    /// let stream = TcpStream::connect("127.0.0.1:8000")
+    ///                        .expect("couldn't bind to address");
+    /// let mut buf = [0; 10];
+    /// let len = stream.peek(&mut buf).expect("peek failed");
+    /// ```
+    // #[stable(feature = "peek", since = "1.18.0")]
+    pub fn peek(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.peek(buf)
+    }
+
+    /// Reads into each buffer of `bufs` in turn with a single system call,
+    /// returning the total number of bytes read.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::TcpStream;
+    ///
+    /// let stream = TcpStream::connect("127.0.0.1:8080")
+    ///                        .expect("Couldn't connect to the server...");
+    /// let mut header = [0; 4];
+    /// let mut body = [0; 256];
+    /// let n = stream.read_vectored(&mut [&mut header, &mut body]).unwrap();
+    /// ```
+    pub fn read_vectored(&self, bufs: &mut [&mut [u8]]) -> io::Result<usize> {
+        self.0.read_vectored(bufs)
+    }
+
+    /// Writes each buffer of `bufs` in turn with a single system call,
+    /// returning the total number of bytes written.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::TcpStream;
+    ///
+    /// let stream = TcpStream::connect("127.0.0.1:8080")
+    ///                        .expect("Couldn't connect to the server...");
+    /// let n = stream.write_vectored(&[b"head", b"body"]).unwrap();
+    /// ```
+    pub fn write_vectored(&self, bufs: &[&[u8]]) -> io::Result<usize> {
+        self.0.write_vectored(bufs)
+    }
+
+    /// Sets the value of the `TCP_NODELAY` option on this socket.
+    ///
+    /// If set, this option disables the Nagle algorithm. This means that
+    /// segments are always sent as soon as possible, even if there is only a
+    /// small amount of data. When not set, data is buffered until there is a
+    /// sufficient amount to send out, thereby avoiding the frequent sending of
+    /// small packets.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::TcpStream;
+    ///
+    /// let stream = TcpStream::connect("127.0.0.1:8080")
+    ///                        .expect("Couldn't connect to the server...");
+    /// stream.set_nodelay(true).expect("set_nodelay call failed");
+    /// ```
+    // #[stable(feature = "net2_mutators", since = "1.9.0")]
+    pub fn set_nodelay(&self, nodelay: bool) -> io::Result<()> {
+        self.0.set_nodelay(nodelay)
+    }
+
+    /// Gets the value of the `TCP_NODELAY` option on this socket.
+    ///
+    /// For more information about this option, see [`set_nodelay`][link].
+    ///
+    /// [link]: #method.set_nodelay
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::TcpStream;
+    ///
+    /// let stream = TcpStream::connect("127.0.0.1:8080")
+    ///                        .expect("Couldn't connect to the server...");
+    /// stream.set_nodelay(true).expect("set_nodelay call failed");
+    /// assert_eq!(stream.nodelay().unwrap_or(false), true);
+    /// ```
+    // #[stable(feature = "net2_mutators", since = "1.9.0")]
+    pub fn nodelay(&self) -> io::Result<bool> {
+        self.0.nodelay()
+    }
+
+    /// Enables TCP keepalive on this socket with the given settings, or
+    /// disables it if `params` is [`None`].
+    ///
+    /// This sets the `SO_KEEPALIVE`, `TCP_KEEPALIVE`, `TCP_KEEPINTVL` and
+    /// `TCP_KEEPCNT` options, which need Windows 10 version 1709 or later.
+    /// Windows takes the idle time and interval in whole seconds, so they are
+    /// rounded up: an idle time of 1.5 seconds is set, and read back by
+    /// [`keepalive`], as 2 seconds. An error with kind `InvalidInput` is
+    /// returned if the idle time or interval is zero.
+    ///
+    /// [`keepalive`]: #method.keepalive
+    /// [`None`]: ../../std/option/enum.Option.html#variant.None
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::{KeepaliveParams, TcpStream};
+    /// use std::time::Duration;
+    ///
+    /// let stream = TcpStream::connect("127.0.0.1:8080")
+    ///                        .expect("Couldn't connect to the server...");
+    /// stream.set_keepalive(Some(KeepaliveParams {
+    ///     idle: Duration::from_secs(60),
+    ///     interval: Duration::from_secs(5),
+    ///     count: 4,
+    /// })).expect("set_keepalive call failed");
+    /// ```
+    pub fn set_keepalive(&self, params: Option<KeepaliveParams>) -> io::Result<()> {
+        self.0.set_keepalive(params.as_ref())
+    }
+
+    /// Gets the keepalive settings of this socket, or [`None`] if keepalive
+    /// is disabled.
+    ///
+    /// For more information about this option, including how the idle time
+    /// and interval are rounded, see [`set_keepalive`][link].
+    ///
+    /// [`None`]: ../../std/option/enum.Option.html#variant.None
+    /// [link]: #method.set_keepalive
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::TcpStream;
+    ///
+    /// let stream = TcpStream::connect("127.0.0.1:8080")
+    ///                        .expect("Couldn't connect to the server...");
+    /// stream.set_keepalive(None).expect("set_keepalive call failed");
+    /// assert_eq!(stream.keepalive().unwrap(), None);
+    /// ```
+    pub fn keepalive(&self) -> io::Result<Option<KeepaliveParams>> {
+        self.0.keepalive()
+    }
+
+    /// Returns statistics about the connection, such as its round-trip time
+    /// and congestion window.
+    ///
+    /// This uses the `SIO_TCP_INFO` control code, which needs Windows 10
+    /// version 1703 or later. Windows doesn't track the RTT variance, so
+    /// [`rtt_var`] is always `None`.
+    ///
+    /// [`rtt_var`]: struct.TcpInfo.html#structfield.rtt_var
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::TcpStream;
+    ///
+    /// let stream = TcpStream::connect("127.0.0.1:8080")
+    ///                        .expect("Couldn't connect to the server...");
+    /// let info = stream.tcp_info().expect("tcp_info call failed");
+    /// println!("rtt {:?}, cwnd {} bytes", info.rtt, info.cwnd);
+    /// ```
+    pub fn tcp_info(&self) -> io::Result<TcpInfo> {
+        self.0.tcp_info()
+    }
+
+    /// Sets the value of the `SO_LINGER` option on this socket.
+    ///
+    /// With a linger time set, closing the socket blocks until pending data
+    /// has been sent or the time runs out. A linger time of zero makes closing
+    /// the socket reset the connection instead; see
+    /// [`close_abortive`][close_abortive]. [`None`] restores the default
+    /// behavior, where the socket is closed in the background. Linger times
+    /// are rounded up to whole seconds.
+    ///
+    /// [close_abortive]: #method.close_abortive
+    /// [`None`]: ../../std/option/enum.Option.html#variant.None
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::TcpStream;
+    /// use std::time::Duration;
+    ///
+    /// let stream = TcpStream::connect("127.0.0.1:8080")
+    ///                        .expect("Couldn't connect to the server...");
+    /// stream.set_linger(Some(Duration::from_secs(5))).expect("set_linger call failed");
+    /// ```
+    pub fn set_linger(&self, linger: Option<Duration>) -> io::Result<()> {
+        self.0.set_linger(linger)
+    }
+
+    /// Gets the value of the `SO_LINGER` option on this socket.
+    ///
+    /// For more information about this option, see [`set_linger`][link].
+    ///
+    /// [link]: #method.set_linger
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::TcpStream;
+    /// use std::time::Duration;
+    ///
+    /// let stream = TcpStream::connect("127.0.0.1:8080")
+    ///                        .expect("Couldn't connect to the server...");
+    /// stream.set_linger(Some(Duration::from_secs(5))).expect("set_linger call failed");
+    /// assert_eq!(stream.linger().unwrap(), Some(Duration::from_secs(5)));
+    /// ```
+    pub fn linger(&self) -> io::Result<Option<Duration>> {
+        self.0.linger()
+    }
+
+    /// Closes the connection by sending a reset (RST) to the peer, discarding
+    /// any data that hasn't been sent yet.
+    ///
+    /// If the stream has been cloned with [`try_clone`][link], the connection
+    /// is only reset once every handle to it has been closed.
+    ///
+    /// [link]: #method.try_clone
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::TcpStream;
+    ///
+    /// let stream = TcpStream::connect("127.0.0.1:8080")
+    ///                        .expect("Couldn't connect to the server...");
+    /// stream.close_abortive().expect("close_abortive call failed");
+    /// ```
+    pub fn close_abortive(self) -> io::Result<()> {
+        // The socket is closed when `self` is dropped on return.
+        self.set_linger(Some(Duration::from_secs(0)))
+    }
+
+    /// Sets the value for the `IP_TTL` option on this socket.
+    ///
+    /// This value sets the time-to-live field that is used in every packet sent
+    /// from this socket.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::TcpStream;
+    ///
+    /// let stream = TcpStream::connect("127.0.0.1:8080")
+    ///                        .expect("Couldn't connect to the server...");
+    /// stream.set_ttl(100).expect("set_ttl call failed");
+    /// ```
+    // #[stable(feature = "net2_mutators", since = "1.9.0")]
+    pub fn set_ttl(&self, ttl: u32) -> io::Result<()> {
+        self.0.set_ttl(ttl)
+    }
+
+    /// Gets the value of the `IP_TTL` option for this socket.
+    ///
+    /// For more information about this option, see [`set_ttl`][link].
+    ///
+    /// [link]: #method.set_ttl
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::TcpStream;
+    ///
+    /// let stream = TcpStream::connect("127.0.0.1:8080")
+    ///                        .expect("Couldn't connect to the server...");
+    /// stream.set_ttl(100).expect("set_ttl call failed");
+    /// assert_eq!(stream.ttl().unwrap_or(0), 100);
+    /// ```
+    // #[stable(feature = "net2_mutators", since = "1.9.0")]
+    pub fn ttl(&self) -> io::Result<u32> {
+        self.0.ttl()
+    }
+
+    /// Sets the value of the `IP_TOS` option for this socket.
+    ///
+    /// This sets the [`Dscp`] codepoint in the type-of-service byte of every
+    /// IPv4 packet sent from this socket. The byte's two ECN bits are left
+    /// cleared.
+    ///
+    /// [`Dscp`]: struct.Dscp.html
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::{Dscp, TcpStream};
+    ///
+    /// let stream = TcpStream::connect("127.0.0.1:8080")
+    ///                        .expect("Couldn't connect to the server...");
+    /// stream.set_tos_v4(Dscp::EF).expect("set_tos_v4 call failed");
+    /// ```
+    pub fn set_tos_v4(&self, dscp: Dscp) -> io::Result<()> {
+        self.0.set_tos_v4(dscp)
+    }
+
+    /// Gets the value of the `IP_TOS` option for this socket.
+    ///
+    /// For more information about this option, see [`set_tos_v4`][link].
+    ///
+    /// [link]: #method.set_tos_v4
+    pub fn tos_v4(&self) -> io::Result<Dscp> {
+        self.0.tos_v4()
+    }
+
+    /// Sets the value of the `IPV6_TCLASS` option for this socket.
+    ///
+    /// This sets the DSCP codepoint in the traffic class of every IPv6 packet
+    /// sent from this socket, the IPv6 counterpart of [`set_tos_v4`].
+    ///
+    /// [`set_tos_v4`]: #method.set_tos_v4
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::{Dscp, TcpStream};
+    ///
+    /// let stream = TcpStream::connect("[::1]:8080")
+    ///                        .expect("Couldn't connect to the server...");
+    /// stream.set_tclass_v6(Dscp::EF).expect("set_tclass_v6 call failed");
+    /// ```
+    pub fn set_tclass_v6(&self, dscp: Dscp) -> io::Result<()> {
+        self.0.set_tclass_v6(dscp)
+    }
+
+    /// Gets the value of the `IPV6_TCLASS` option for this socket.
+    ///
+    /// For more information about this option, see [`set_tclass_v6`][link].
+    ///
+    /// [link]: #method.set_tclass_v6
+    pub fn tclass_v6(&self) -> io::Result<Dscp> {
+        self.0.tclass_v6()
+    }
+
+    /// Sets the value of the `IP_DONTFRAGMENT` option for this socket.
+    ///
+    /// If enabled, IPv4 packets sent on this connection have the
+    /// don't-fragment flag set. TCP already sizes its segments to fit the
+    /// path, and with the flag set a router that can't forward one reports
+    /// back rather than fragmenting it, which lowers [`path_mtu`].
+    ///
+    /// [`path_mtu`]: #method.path_mtu
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::TcpStream;
+    ///
+    /// let stream = TcpStream::connect("127.0.0.1:8080")
+    ///                        .expect("Couldn't connect to the server...");
+    /// stream.set_dont_fragment(true).expect("set_dont_fragment call failed");
+    /// ```
+    pub fn set_dont_fragment(&self, dont_fragment: bool) -> io::Result<()> {
+        self.0.set_dont_fragment(dont_fragment)
+    }
+
+    /// Gets the value of the `IP_DONTFRAGMENT` option for this socket.
+    ///
+    /// For more information about this option, see [`set_dont_fragment`][link].
+    ///
+    /// [link]: #method.set_dont_fragment
+    pub fn dont_fragment(&self) -> io::Result<bool> {
+        self.0.dont_fragment()
+    }
+
+    /// Gets the value of the `IP_MTU` option for this socket.
+    ///
+    /// This is the path MTU the system currently knows of towards the peer of
+    /// this connection, which bounds the size of the segments sent on it.
+    pub fn path_mtu(&self) -> io::Result<u32> {
+        self.0.path_mtu()
+    }
+
+    /// Sets the value of the `IPV6_DONTFRAG` option for this socket.
+    ///
+    /// This is the IPv6 counterpart of [`set_dont_fragment`]. IPv6 routers
+    /// never fragment, so this only stops the sending host from doing so.
+    ///
+    /// [`set_dont_fragment`]: #method.set_dont_fragment
+    pub fn set_dont_fragment_v6(&self, dont_fragment: bool) -> io::Result<()> {
+        self.0.set_dont_fragment_v6(dont_fragment)
+    }
+
+    /// Gets the value of the `IPV6_DONTFRAG` option for this socket.
+    ///
+    /// For more information about this option, see
+    /// [`set_dont_fragment_v6`][link].
+    ///
+    /// [link]: #method.set_dont_fragment_v6
+    pub fn dont_fragment_v6(&self) -> io::Result<bool> {
+        self.0.dont_fragment_v6()
+    }
+
+    /// Gets the value of the `IPV6_MTU` option for this socket.
+    ///
+    /// This is the IPv6 counterpart of [`path_mtu`].
+    ///
+    /// [`path_mtu`]: #method.path_mtu
+    pub fn path_mtu_v6(&self) -> io::Result<u32> {
+        self.0.path_mtu_v6()
+    }
+
+    /// Get the value of the `SO_ERROR` option on this socket.
+    ///
+    /// This will retrieve the stored error in the underlying socket, clearing
+    /// the field in the process. This can be useful for checking errors between
+    /// calls.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::TcpStream;
+    ///
+    /// let stream = TcpStream::connect("127.0.0.1:8080")
+    ///                        .expect("Couldn't connect to the server...");
+    /// stream.take_error().expect("No error was expected...");
+    /// ```
+    // #[stable(feature = "net2_mutators", since = "1.9.0")]
+    pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+        self.0.take_error()
+    }
+
+    /// Moves this TCP stream into or out of nonblocking mode.
+    ///
+    /// This will result in `read`, `write`, `recv` and `send` operations
+    /// becoming nonblocking, i.e. immediately returning from their calls.
+    /// If the IO operation is successful, `Ok` is returned and no further
+    /// action is required. If the IO operation could not be completed and needs
+    /// to be retried, an error with kind [`io::ErrorKind::WouldBlock`] is
+    /// returned.
+    ///
+    /// On Windows calling `ioctlsocket` corresponds to calling
+    /// `ioctlsocket FIONBIO`.
+    ///
+    /// [`io::ErrorKind::WouldBlock`]: ../io/enum.ErrorKind.html#variant.WouldBlock
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::io::{self, Read};
+    /// use std::net::TcpStream;
+    ///
+    /// let mut stream = TcpStream::connect("127.0.0.1:7878")
+    ///     .expect("Couldn't connect to the server...");
+    /// stream.set_nonblocking(true).expect("set_nonblocking call failed");
+    ///
+    /// # fn wait_for_fd() { unimplemented!() }
+    /// let mut buf = vec![];
+    /// loop {
+    ///     match stream.read_to_end(&mut buf) {
+    ///         Ok(_) => break,
+    ///         Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {
+    ///             // wait until network socket is ready, typically implemented
+    ///             // via platform-specific APIs such as epoll or IOCP
+    ///             wait_for_fd();
+    ///         }
+    ///         Err(e) => panic!("encountered IO error: {}", e),
+    ///     };
+    /// };
+    /// println!("bytes: {:?}", buf);
+    /// ```
+    // #[stable(feature = "net2_mutators", since = "1.9.0")]
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        self.0.set_nonblocking(nonblocking)
+    }
+}
+
+// #[stable(feature = "rust1", since = "1.0.0")]
+impl Read for TcpStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> { self.0.read(buf) }
+
+    #[inline]
+    unsafe fn initializer(&self) -> Initializer {
+        Initializer::nop()
+    }
+}
+// #[stable(feature = "rust1", since = "1.0.0")]
+impl Write for TcpStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> { self.0.write(buf) }
+    fn flush(&mut self) -> io::Result<()> { Ok(()) }
+}
+// #[stable(feature = "rust1", since = "1.0.0")]
+impl<'a> Read for &'a TcpStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> { self.0.read(buf) }
+
+    #[inline]
+    unsafe fn initializer(&self) -> Initializer {
+        Initializer::nop()
+    }
+}
+// #[stable(feature = "rust1", since = "1.0.0")]
+impl<'a> Write for &'a TcpStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> { self.0.write(buf) }
+    fn flush(&mut self) -> io::Result<()> { Ok(()) }
+}
+
+impl AsInner<net_imp::TcpStream> for TcpStream {
+    fn as_inner(&self) -> &net_imp::TcpStream { &self.0 }
+}
+
+impl FromInner<net_imp::TcpStream> for TcpStream {
+    fn from_inner(inner: net_imp::TcpStream) -> TcpStream { TcpStream(inner) }
+}
+
+impl IntoInner<net_imp::TcpStream> for TcpStream {
+    fn into_inner(self) -> net_imp::TcpStream { self.0 }
+}
+
+// #[stable(feature = "rust1", since = "1.0.0")]
+impl fmt::Debug for TcpStream {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl TcpListener {
+    /// Creates a new `TcpListener` which will be bound to the specified
+    /// address.
+    ///
+    /// The returned listener is ready for accepting connections.
+    ///
+    /// Binding with a port number of 0 will request that the OS assigns a port
+    /// to this listener. The port allocated can be queried via the
+    /// [`local_addr`] method.
+    ///
+    /// The address type can be any implementor of [`ToSocketAddrs`] trait. See
+    /// its documentation for concrete examples.
+    ///
+    /// If `addr` yields multiple addresses, `bind` will be attempted with
+    /// each of the addresses until one succeeds and returns the listener. If
+    /// none of the addresses succeed in creating a listener, the error returned
+    /// from the last attempt (the last address) is returned.
+    ///
+    /// [`local_addr`]: #method.local_addr
+    /// [`ToSocketAddrs`]: ../../std/net/trait.ToSocketAddrs.html
+    ///
+    /// # Examples
+    ///
+    /// Create a TCP listener bound to `127.0.0.1:80`:
+    ///
+    /// ```ignore
+    /// use std::net::TcpListener;
+    ///
+    /// let listener = TcpListener::bind("127.0.0.1:80").unwrap();
+    /// ```
+    // #[stable(feature = "rust1", since = "1.0.0")]
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> io::Result<TcpListener> {
+        super::each_addr(addr, net_imp::TcpListener::bind).map(TcpListener)
+    }
+
+    /// Returns the local socket address of this listener.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4, TcpListener};
+    ///
+    /// let listener = TcpListener::bind("127.0.0.1:8080").unwrap();
+    /// assert_eq!(listener.local_addr().unwrap(),
+    ///            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(127, 0, 0, 1), 8080)));
+    /// ```
+    // #[stable(feature = "rust1", since = "1.0.0")]
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.0.socket_addr()
+    }
+
+    /// Creates a new independently owned handle to the underlying socket.
+    ///
+    /// The returned [`TcpListener`] is a reference to the same socket that this
+    /// object references. Both handles can be used to accept incoming
+    /// connections and options set on one listener will affect the other.
+    ///
+    /// [`TcpListener`]: ../../std/net/struct.TcpListener.html
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::TcpListener;
+    ///
+    /// let listener = TcpListener::bind("127.0.0.1:8080").unwrap();
+    /// let listener_clone = listener.try_clone().unwrap();
+    /// ```
+    // #[stable(feature = "rust1", since = "1.0.0")]
+    pub fn try_clone(&self) -> io::Result<TcpListener> {
+        self.0.duplicate().map(TcpListener)
+    }
+
+    /// Accept a new incoming connection from this listener.
+    ///
+    /// This function will block the calling thread until a new TCP connection
+    /// is established. When established, the corresponding [`TcpStream`] and the
+    /// remote peer's address will be returned.
+    ///
+    /// [`TcpStream`]: ../../std/net/struct.TcpStream.html
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::TcpListener;
+    ///
+    /// let listener = TcpListener::bind("127.0.0.1:8080").unwrap();
+    /// match listener.accept() {
+    ///     Ok((_socket, addr)) => println!("new client: {:?}", addr),
+    ///     Err(e) => println!("couldn't get client: {:?}", e),
+    /// }
+    /// ```
+    // #[stable(feature = "rust1", since = "1.0.0")]
+    pub fn accept(&self) -> io::Result<(TcpStream, SocketAddr)> {
+        self.0.accept().map(|(a, b)| (TcpStream(a), b))
+    }
+
+    /// Returns an iterator over the connections being received on this
+    /// listener.
+    ///
+    /// The returned iterator will never return [`None`] and will also not yield
+    /// the peer's [`SocketAddr`] structure. Iterating over it is equivalent to
+    /// calling [`accept`] in a loop.
+    ///
+    /// [`None`]: ../../std/option/enum.Option.html#variant.None
+    /// [`SocketAddr`]: ../../std/net/enum.SocketAddr.html
+    /// [`accept`]: #method.accept
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::TcpListener;
+    ///
+    /// let listener = TcpListener::bind("127.0.0.1:80").unwrap();
+    ///
+    /// for stream in listener.incoming() {
+    ///     match stream {
+    ///         Ok(stream) => {
+    ///             println!("new client!");
+    ///         }
+    ///         Err(e) => { /* connection failed */ }
+    ///     }
+    /// }
+    /// ```
+    // #[stable(feature = "rust1", since = "1.0.0")]
+    pub fn incoming(&self) -> Incoming {
+        Incoming { listener: self }
+    }
+
+    /// Sets the value for the `IP_TTL` option on this socket.
+    ///
+    /// This value sets the time-to-live field that is used in every packet sent
+    /// from this socket.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::TcpListener;
+    ///
+    /// let listener = TcpListener::bind("127.0.0.1:80").unwrap();
+    /// listener.set_ttl(100).expect("could not set TTL");
+    /// ```
+    // #[stable(feature = "net2_mutators", since = "1.9.0")]
+    pub fn set_ttl(&self, ttl: u32) -> io::Result<()> {
+        self.0.set_ttl(ttl)
+    }
+
+    /// Gets the value of the `IP_TTL` option for this socket.
+    ///
+    /// For more information about this option, see [`set_ttl`][link].
+    ///
+    /// [link]: #method.set_ttl
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::TcpListener;
+    ///
+    /// let listener = TcpListener::bind("127.0.0.1:80").unwrap();
+    /// listener.set_ttl(100).expect("could not set TTL");
+    /// assert_eq!(listener.ttl().unwrap_or(0), 100);
+    /// ```
+    // #[stable(feature = "net2_mutators", since = "1.9.0")]
+    pub fn ttl(&self) -> io::Result<u32> {
+        self.0.ttl()
+    }
+
+    /// Sets the value of the `IP_TOS` option for this socket.
+    ///
+    /// For more information about this option, see
+    /// [`TcpStream::set_tos_v4`][link].
+    ///
+    /// [link]: struct.TcpStream.html#method.set_tos_v4
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::{Dscp, TcpListener};
+    ///
+    /// let listener = TcpListener::bind("127.0.0.1:80").unwrap();
+    /// listener.set_tos_v4(Dscp::EF).expect("set_tos_v4 call failed");
+    /// ```
+    pub fn set_tos_v4(&self, dscp: Dscp) -> io::Result<()> {
+        self.0.set_tos_v4(dscp)
+    }
+
+    /// Gets the value of the `IP_TOS` option for this socket.
+    ///
+    /// For more information about this option, see [`set_tos_v4`][link].
+    ///
+    /// [link]: #method.set_tos_v4
+    pub fn tos_v4(&self) -> io::Result<Dscp> {
+        self.0.tos_v4()
+    }
+
+    /// Sets the value of the `IPV6_TCLASS` option for this socket.
+    ///
+    /// For more information about this option, see
+    /// [`TcpStream::set_tclass_v6`][link].
+    ///
+    /// [link]: struct.TcpStream.html#method.set_tclass_v6
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::{Dscp, TcpListener};
+    ///
+    /// let listener = TcpListener::bind("[::1]:80").unwrap();
+    /// listener.set_tclass_v6(Dscp::EF).expect("set_tclass_v6 call failed");
+    /// ```
+    pub fn set_tclass_v6(&self, dscp: Dscp) -> io::Result<()> {
+        self.0.set_tclass_v6(dscp)
+    }
+
+    /// Gets the value of the `IPV6_TCLASS` option for this socket.
+    ///
+    /// For more information about this option, see [`set_tclass_v6`][link].
+    ///
+    /// [link]: #method.set_tclass_v6
+    pub fn tclass_v6(&self) -> io::Result<Dscp> {
+        self.0.tclass_v6()
+    }
+
+    /// Sets the value for the `IPV6_V6ONLY` option on this socket.
+    ///
+    /// If this is set to `true` then the socket is restricted to sending and
+    /// receiving IPv6 packets only. In this case two IPv4 and IPv6 applications
+    /// can bind the same port at the same time.
+    ///
+    /// If this is set to `false` then the socket can be used to send and
+    /// receive packets from an IPv4-mapped IPv6 address.
+    // #[stable(feature = "net2_mutators", since = "1.9.0")]
+    pub fn set_only_v6(&self, only_v6: bool) -> io::Result<()> {
+        self.0.set_only_v6(only_v6)
+    }
+
+    /// Gets the value of the `IPV6_V6ONLY` option for this socket.
+    ///
+    /// For more information about this option, see [`set_only_v6`][link].
+    ///
+    /// [link]: #method.set_only_v6
+    // #[stable(feature = "net2_mutators", since = "1.9.0")]
+    pub fn only_v6(&self) -> io::Result<bool> {
+        self.0.only_v6()
+    }
+
+    /// Get the value of the `SO_ERROR` option on this socket.
+    ///
+    /// This will retrieve the stored error in the underlying socket, clearing
+    /// the field in the process. This can be useful for checking errors between
+    /// calls.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::TcpListener;
+    ///
+    /// let listener = TcpListener::bind("127.0.0.1:80").unwrap();
+    /// listener.take_error().expect("No error was expected");
+    /// ```
+    // #[stable(feature = "net2_mutators", since = "1.9.0")]
+    pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+        self.0.take_error()
+    }
+
+    /// Moves this TCP stream into or out of nonblocking mode.
+    ///
+    /// This will result in the `accept` operation becoming nonblocking,
+    /// i.e. immediately returning from their calls. If the IO operation is
+    /// successful, `Ok` is returned and no further action is required. If the
+    /// IO operation could not be completed and needs to be retried, an error
+    /// with kind [`io::ErrorKind::WouldBlock`] is returned.
+    ///
+    /// On Windows calling `ioctlsocket` corresponds to calling
+    /// `ioctlsocket FIONBIO`.
+    ///
+    /// [`io::ErrorKind::WouldBlock`]: ../io/enum.ErrorKind.html#variant.WouldBlock
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::io;
+    /// use std::net::TcpListener;
+    ///
+    /// let listener = TcpListener::bind("127.0.0.1:7878").unwrap();
+    /// listener.set_nonblocking(true).expect("Cannot set non-blocking");
+    ///
+    /// # fn wait_for_fd() { unimplemented!() }
+    /// # fn handle_connection(stream: std::net::TcpStream) { unimplemented!() }
+    /// for stream in listener.incoming() {
+    ///     match stream {
+    ///         Ok(s) => {
+    ///             // do something with the TcpStream
+    ///             handle_connection(s);
+    ///         }
+    ///         Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {
+    ///             // wait until network socket is ready, typically implemented
+    ///             // via platform-specific APIs such as epoll or IOCP
+    ///             wait_for_fd();
+    ///             continue;
+    ///         }
+    ///         Err(e) => panic!("encountered IO error: {}", e),
+    ///     }
+    /// }
+    /// ```
+    // #[stable(feature = "net2_mutators", since = "1.9.0")]
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        self.0.set_nonblocking(nonblocking)
+    }
+}
+
+// #[stable(feature = "rust1", since = "1.0.0")]
+impl<'a> Iterator for Incoming<'a> {
+    type Item = io::Result<TcpStream>;
+    fn next(&mut self) -> Option<io::Result<TcpStream>> {
+        Some(self.listener.accept().map(|p| p.0))
+    }
+}
+
+impl AsInner<net_imp::TcpListener> for TcpListener {
+    fn as_inner(&self) -> &net_imp::TcpListener { &self.0 }
+}
+
+impl FromInner<net_imp::TcpListener> for TcpListener {
+    fn from_inner(inner: net_imp::TcpListener) -> TcpListener {
+        TcpListener(inner)
+    }
+}
+
+impl IntoInner<net_imp::TcpListener> for TcpListener {
+    fn into_inner(self) -> net_imp::TcpListener { self.0 }
+}
+
+// #[stable(feature = "rust1", since = "1.0.0")]
+impl fmt::Debug for TcpListener {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// A builder for a [`TcpListener`], for socket options that only take effect
+/// if they are set before the socket is bound.
+///
+/// [`TcpListener`]: struct.TcpListener.html
+///
+/// # Examples
+///
+/// ```ignore
+/// use std::net::TcpBuilder;
+///
+/// let listener = TcpBuilder::new()
+///     .exclusive_address_use(true)
+///     .backlog(1024)
+///     .bind("0.0.0.0:8080")
+///     .unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct TcpBuilder {
+    opts: net_imp::BindOpts,
+    backlog: i32,
+}
+
+impl TcpBuilder {
+    /// Creates a builder with the same settings as [`TcpListener::bind`],
+    /// except that `SO_REUSEADDR` is never set unless asked for.
+    ///
+    /// [`TcpListener::bind`]: struct.TcpListener.html#method.bind
+    pub fn new() -> TcpBuilder {
+        TcpBuilder {
+            opts: net_imp::BindOpts::default(),
+            backlog: 128,
+        }
+    }
+
+    /// Sets the `SO_REUSEADDR` option.
+    ///
+    /// Note that on Windows this lets the socket bind an address and port
+    /// that another socket is already bound to, unless that socket set
+    /// [`exclusive_address_use`][link]. Servers should usually set the latter
+    /// instead, so that no other process can take over their port.
+    ///
+    /// [link]: #method.exclusive_address_use
+    pub fn reuse_address(&mut self, reuse: bool) -> &mut TcpBuilder {
+        self.opts.reuse_address = reuse;
+        self
+    }
+
+    /// Sets the `SO_EXCLUSIVEADDRUSE` option, which stops any other socket
+    /// from binding the same address and port, even with `SO_REUSEADDR`.
+    pub fn exclusive_address_use(&mut self, exclusive: bool) -> &mut TcpBuilder {
+        self.opts.exclusive_address_use = exclusive;
+        self
+    }
+
+    /// Sets the `SO_REUSEPORT` option, which lets several sockets bind the
+    /// same address and port and share the incoming connections.
+    ///
+    /// Windows has no `SO_REUSEPORT`, so [`bind`] fails with an error of kind
+    /// `InvalidInput` if this is set.
+    ///
+    /// [`bind`]: #method.bind
+    pub fn reuse_port(&mut self, reuse: bool) -> &mut TcpBuilder {
+        self.opts.reuse_port = reuse;
+        self
+    }
+
+    /// Sets the `IPV6_V6ONLY` option, as described for
+    /// [`TcpListener::set_only_v6`]. Binding an IPv4 address fails if this is
+    /// set.
+    ///
+    /// [`TcpListener::set_only_v6`]: struct.TcpListener.html#method.set_only_v6
+    pub fn only_v6(&mut self, only_v6: bool) -> &mut TcpBuilder {
+        self.opts.only_v6 = Some(only_v6);
+        self
+    }
+
+    /// Sets the `SO_SNDBUF` option, the size of the send buffer of the
+    /// listener and of the streams it accepts.
+    ///
+    /// [`bind`] fails with an error of kind `InvalidInput` if the size
+    /// doesn't fit in an `i32`.
+    ///
+    /// [`bind`]: #method.bind
+    pub fn send_buffer_size(&mut self, size: usize) -> &mut TcpBuilder {
+        self.opts.send_buffer_size = Some(size);
+        self
+    }
+
+    /// Sets the `SO_RCVBUF` option, the size of the receive buffer of the
+    /// listener and of the streams it accepts.
+    ///
+    /// As with [`send_buffer_size`], the size must fit in an `i32`.
+    ///
+    /// [`send_buffer_size`]: #method.send_buffer_size
+    pub fn recv_buffer_size(&mut self, size: usize) -> &mut TcpBuilder {
+        self.opts.recv_buffer_size = Some(size);
+        self
+    }
+
+    /// Sets the maximum number of pending connections passed to `listen`.
+    ///
+    /// The default is 128.
+    pub fn backlog(&mut self, backlog: i32) -> &mut TcpBuilder {
+        self.backlog = backlog;
+        self
+    }
+
+    /// Creates a socket with the configured options, binds it to `addr` and
+    /// starts listening.
+    ///
+    /// If `addr` yields multiple addresses, a socket is created for each of
+    /// them in turn until one can be bound, as with [`TcpListener::bind`].
+    ///
+    /// [`TcpListener::bind`]: struct.TcpListener.html#method.bind
+    pub fn bind<A: ToSocketAddrs>(&self, addr: A) -> io::Result<TcpListener> {
+        super::each_addr(addr, |addr| {
+            net_imp::TcpListener::bind_opts(addr, &self.opts, self.backlog)
+        }).map(TcpListener)
+    }
+}
+
+impl Default for TcpBuilder {
+    fn default() -> TcpBuilder {
+        TcpBuilder::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, ErrorKind};
+    use std::io::prelude::*;
+    use net::*;
+    use net::test::{next_test_ip4, next_test_ip6};
+    use std::sync::mpsc::channel;
+    use sys_common::AsInner;
+    use std::time::{Instant, Duration};
+    use std::thread;
+
+    fn each_ip(f: &mut dyn FnMut(SocketAddr)) {
+        f(next_test_ip4());
+        f(next_test_ip6());
+    }
+
+    macro_rules! t {
+        ($e:expr) => {
+            match $e {
+                Ok(t) => t,
+                Err(e) => panic!("received error for `{}`: {}", stringify!($e), e),
+            }
+        }
+    }
+
+    #[test]
+    fn bind_error() {
+        match TcpListener::bind("1.1.1.1:9999") {
+            Ok(..) => panic!(),
+            Err(e) =>
+                assert_eq!(e.kind(), ErrorKind::AddrNotAvailable),
+        }
+    }
+
+    #[test]
+    fn connect_error() {
+        match TcpStream::connect("0.0.0.0:1") {
+            Ok(..) => panic!(),
+            Err(e) => assert!(e.kind() == ErrorKind::ConnectionRefused ||
+                              e.kind() == ErrorKind::InvalidInput ||
+                              e.kind() == ErrorKind::AddrInUse ||
+                              e.kind() == ErrorKind::AddrNotAvailable,
+                              "bad error: {} {:?}", e, e.kind()),
+        }
+    }
+
+    #[test]
+    fn listen_localhost() {
+        let socket_addr = next_test_ip4();
+        let listener = t!(TcpListener::bind(&socket_addr));
+
+        let _t = thread::spawn(move || {
+            let mut stream = t!(TcpStream::connect(&("localhost",
+                                                     socket_addr.port())));
+            t!(stream.write(&[144]));
+        });
+
+        let mut stream = t!(listener.accept()).0;
+        let mut buf = [0];
+        t!(stream.read(&mut buf));
+        assert!(buf[0] == 144);
+    }
+
+    #[test]
+    fn connect_loopback() {
+        each_ip(&mut |addr| {
+            let acceptor = t!(TcpListener::bind(&addr));
+
+            let _t = thread::spawn(move|| {
+                let host = match addr {
+                    SocketAddr::V4(..) => "127.0.0.1",
+                    SocketAddr::V6(..) => "::1",
+                };
+                let mut stream = t!(TcpStream::connect(&(host, addr.port())));
+                t!(stream.write(&[66]));
+            });
+
+            let mut stream = t!(acceptor.accept()).0;
+            let mut buf = [0];
+            t!(stream.read(&mut buf));
+            assert!(buf[0] == 66);
+        })
+    }
+
+    #[test]
+    fn smoke_test() {
+        each_ip(&mut |addr| {
+            let acceptor = t!(TcpListener::bind(&addr));
+
+            let (tx, rx) = channel();
+            let _t = thread::spawn(move|| {
+                let mut stream = t!(TcpStream::connect(&addr));
+                t!(stream.write(&[99]));
+                tx.send(t!(stream.local_addr())).unwrap();
+            });
+
+            let (mut stream, addr) = t!(acceptor.accept());
+            let mut buf = [0];
+            t!(stream.read(&mut buf));
+            assert!(buf[0] == 99);
+            assert_eq!(addr, t!(rx.recv()));
+        })
+    }
+
+    #[test]
+    fn read_eof() {
+        each_ip(&mut |addr| {
+            let acceptor = t!(TcpListener::bind(&addr));
+
+            let _t = thread::spawn(move|| {
+                let _stream = t!(TcpStream::connect(&addr));
+                // Close
+            });
+
+            let mut stream = t!(acceptor.accept()).0;
+            let mut buf = [0];
+            let nread = t!(stream.read(&mut buf));
+            assert_eq!(nread, 0);
+            let nread = t!(stream.read(&mut buf));
+            assert_eq!(nread, 0);
+        })
+    }
+
+    #[test]
+    fn write_close() {
+        each_ip(&mut |addr| {
+            let acceptor = t!(TcpListener::bind(&addr));
+
+            let (tx, rx) = channel();
+            let _t = thread::spawn(move|| {
+                drop(t!(TcpStream::connect(&addr)));
+                tx.send(()).unwrap();
+            });
+
+            let mut stream = t!(acceptor.accept()).0;
+            rx.recv().unwrap();
+            let buf = [0];
+            match stream.write(&buf) {
+                Ok(..) => {}
+                Err(e) => {
+                    assert!(e.kind() == ErrorKind::ConnectionReset ||
+                            e.kind() == ErrorKind::BrokenPipe ||
+                            e.kind() == ErrorKind::ConnectionAborted,
+                            "unknown error: {}", e);
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn multiple_connect_serial() {
+        each_ip(&mut |addr| {
+            let max = 10;
+            let acceptor = t!(TcpListener::bind(&addr));
+
+            let _t = thread::spawn(move|| {
+                for _ in 0..max {
+                    let mut stream = t!(TcpStream::connect(&addr));
+                    t!(stream.write(&[99]));
+                }
+            });
+
+            for stream in acceptor.incoming().take(max) {
+                let mut stream = t!(stream);
+                let mut buf = [0];
+                t!(stream.read(&mut buf));
+                assert_eq!(buf[0], 99);
+            }
+        })
+    }
+
+    #[test]
+    fn multiple_connect_interleaved_greedy_schedule() {
+        const MAX: usize = 10;
+        each_ip(&mut |addr| {
+            let acceptor = t!(TcpListener::bind(&addr));
+
+            let _t = thread::spawn(move|| {
+                let acceptor = acceptor;
+                for (i, stream) in acceptor.incoming().enumerate().take(MAX) {
+                    // Start another thread to handle the connection
+                    let _t = thread::spawn(move|| {
+                        let mut stream = t!(stream);
+                        let mut buf = [0];
+                        t!(stream.read(&mut buf));
+                        assert!(buf[0] == i as u8);
+                    });
+                }
+            });
+
+            connect(0, addr);
+        });
+
+        fn connect(i: usize, addr: SocketAddr) {
+            if i == MAX { return }
+
+            let t = thread::spawn(move|| {
+                let mut stream = t!(TcpStream::connect(&addr));
+                // Connect again before writing
+                connect(i + 1, addr);
+                t!(stream.write(&[i as u8]));
+            });
+            t.join().ok().unwrap();
+        }
+    }
+
+    #[test]
+    fn multiple_connect_interleaved_lazy_schedule() {
+        const MAX: usize = 10;
+        each_ip(&mut |addr| {
+            let acceptor = t!(TcpListener::bind(&addr));
+
+            let _t = thread::spawn(move|| {
+                for stream in acceptor.incoming().take(MAX) {
+                    // Start another thread to handle the connection
+                    let _t = thread::spawn(move|| {
+                        let mut stream = t!(stream);
+                        let mut buf = [0];
+                        t!(stream.read(&mut buf));
+                        assert!(buf[0] == 99);
+                    });
+                }
+            });
+
+            connect(0, addr);
+        });
+
+        fn connect(i: usize, addr: SocketAddr) {
+            if i == MAX { return }
+
+            let t = thread::spawn(move|| {
+                let mut stream = t!(TcpStream::connect(&addr));
+                connect(i + 1, addr);
+                t!(stream.write(&[99]));
+            });
+            t.join().ok().unwrap();
+        }
+    }
+
+    #[test]
+    fn socket_and_peer_name() {
+        each_ip(&mut |addr| {
+            let listener = t!(TcpListener::bind(&addr));
+            let so_name = t!(listener.local_addr());
+            assert_eq!(addr, so_name);
+            let _t = thread::spawn(move|| {
+                t!(listener.accept());
+            });
+
+            let stream = t!(TcpStream::connect(&addr));
+            assert_eq!(addr, t!(stream.peer_addr()));
+        })
+    }
+
+    #[test]
+    fn partial_read() {
+        each_ip(&mut |addr| {
+            let (tx, rx) = channel();
+            let srv = t!(TcpListener::bind(&addr));
+            let _t = thread::spawn(move|| {
+                let mut cl = t!(srv.accept()).0;
+                cl.write(&[10]).unwrap();
+                let mut b = [0];
+                t!(cl.read(&mut b));
+                tx.send(()).unwrap();
+            });
+
+            let mut c = t!(TcpStream::connect(&addr));
+            let mut b = [0; 10];
+            assert_eq!(c.read(&mut b).unwrap(), 1);
+            t!(c.write(&[1]));
+            rx.recv().unwrap();
+        })
+    }
+
+    #[test]
+    fn double_bind() {
+        each_ip(&mut |addr| {
+            let _listener = t!(TcpListener::bind(&addr));
+            match TcpListener::bind(&addr) {
+                Ok(..) => panic!(),
+                Err(e) => {
+                    assert!(e.kind() == ErrorKind::ConnectionRefused ||
+                            e.kind() == ErrorKind::Other ||
+                            e.kind() == ErrorKind::AddrInUse,
+                            "unknown error: {} {:?}", e, e.kind());
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn tcp_clone_smoke() {
+        each_ip(&mut |addr| {
+            let acceptor = t!(TcpListener::bind(&addr));
+
+            let _t = thread::spawn(move|| {
+                let mut s = t!(TcpStream::connect(&addr));
+                let mut buf = [0, 0];
+                assert_eq!(s.read(&mut buf).unwrap(), 1);
+                assert_eq!(buf[0], 1);
+                t!(s.write(&[2]));
+            });
+
+            let mut s1 = t!(acceptor.accept()).0;
+            let s2 = t!(s1.try_clone());
+
+            let (tx1, rx1) = channel();
+            let (tx2, rx2) = channel();
+            let _t = thread::spawn(move|| {
+                let mut s2 = s2;
+                rx1.recv().unwrap();
+                t!(s2.write(&[1]));
+                tx2.send(()).unwrap();
+            });
+            tx1.send(()).unwrap();
+            let mut buf = [0, 0];
+            assert_eq!(s1.read(&mut buf).unwrap(), 1);
+            rx2.recv().unwrap();
+        })
+    }
+
+    #[test]
+    fn tcp_clone_two_read() {
+        each_ip(&mut |addr| {
+            let acceptor = t!(TcpListener::bind(&addr));
+            let (tx1, rx) = channel();
+            let tx2 = tx1.clone();
+
+            let _t = thread::spawn(move|| {
+                let mut s = t!(TcpStream::connect(&addr));
+                t!(s.write(&[1]));
+                rx.recv().unwrap();
+                t!(s.write(&[2]));
+                rx.recv().unwrap();
+            });
+
+            let mut s1 = t!(acceptor.accept()).0;
+            let s2 = t!(s1.try_clone());
+
+            let (done, rx) = channel();
+            let _t = thread::spawn(move|| {
+                let mut s2 = s2;
+                let mut buf = [0, 0];
+                t!(s2.read(&mut buf));
+                tx2.send(()).unwrap();
+                done.send(()).unwrap();
+            });
+            let mut buf = [0, 0];
+            t!(s1.read(&mut buf));
+            tx1.send(()).unwrap();
+
+            rx.recv().unwrap();
+        })
+    }
+
+    #[test]
+    fn tcp_clone_two_write() {
+        each_ip(&mut |addr| {
+            let acceptor = t!(TcpListener::bind(&addr));
+
+            let _t = thread::spawn(move|| {
+                let mut s = t!(TcpStream::connect(&addr));
+                let mut buf = [0, 1];
+                t!(s.read(&mut buf));
+                t!(s.read(&mut buf));
+            });
+
+            let mut s1 = t!(acceptor.accept()).0;
+            let s2 = t!(s1.try_clone());
+
+            let (done, rx) = channel();
+            let _t = thread::spawn(move|| {
+                let mut s2 = s2;
+                t!(s2.write(&[1]));
+                done.send(()).unwrap();
+            });
+            t!(s1.write(&[2]));
+
+            rx.recv().unwrap();
+        })
+    }
+
+    #[test]
+    fn shutdown_smoke() {
+        each_ip(&mut |addr| {
+            let a = t!(TcpListener::bind(&addr));
+            let _t = thread::spawn(move|| {
+                let mut c = t!(a.accept()).0;
+                let mut b = [0];
+                assert_eq!(c.read(&mut b).unwrap(), 0);
+                t!(c.write(&[1]));
+            });
+
+            let mut s = t!(TcpStream::connect(&addr));
+            t!(s.shutdown(Shutdown::Write));
+            assert!(s.write(&[1]).is_err());
+            let mut b = [0, 0];
+            assert_eq!(t!(s.read(&mut b)), 1);
+            assert_eq!(b[0], 1);
+        })
+    }
+
+    #[test]
+    fn close_readwrite_smoke() {
+        each_ip(&mut |addr| {
+            let a = t!(TcpListener::bind(&addr));
+            let (tx, rx) = channel::<()>();
+            let _t = thread::spawn(move|| {
+                let _s = t!(a.accept());
+                let _ = rx.recv();
+            });
+
+            let mut b = [0];
+            let mut s = t!(TcpStream::connect(&addr));
+            let mut s2 = t!(s.try_clone());
+
+            // closing should prevent reads/writes
+            t!(s.shutdown(Shutdown::Write));
+            assert!(s.write(&[0]).is_err());
+            t!(s.shutdown(Shutdown::Read));
+            assert_eq!(s.read(&mut b).unwrap(), 0);
+
+            // closing should affect previous handles
+            assert!(s2.write(&[0]).is_err());
+            assert_eq!(s2.read(&mut b).unwrap(), 0);
+
+            // closing should affect new handles
+            let mut s3 = t!(s.try_clone());
+            assert!(s3.write(&[0]).is_err());
+            assert_eq!(s3.read(&mut b).unwrap(), 0);
+
+            // make sure these don't die
+            let _ = s2.shutdown(Shutdown::Read);
+            let _ = s2.shutdown(Shutdown::Write);
+            let _ = s3.shutdown(Shutdown::Read);
+            let _ = s3.shutdown(Shutdown::Write);
+            drop(tx);
+        })
+    }
+
+    #[test]
+    fn close_read_wakes_up() {
+        each_ip(&mut |addr| {
+            let a = t!(TcpListener::bind(&addr));
+            let (tx1, rx) = channel::<()>();
+            let _t = thread::spawn(move|| {
+                let _s = t!(a.accept());
+                let _ = rx.recv();
+            });
+
+            let s = t!(TcpStream::connect(&addr));
+            let s2 = t!(s.try_clone());
+            let (tx, rx) = channel();
+            let _t = thread::spawn(move|| {
+                let mut s2 = s2;
+                assert_eq!(t!(s2.read(&mut [0])), 0);
+                tx.send(()).unwrap();
+            });
+            // this should wake up the child thread
+            t!(s.shutdown(Shutdown::Read));
+
+            // this test will never finish if the child doesn't wake up
+            rx.recv().unwrap();
+            drop(tx1);
+        })
+    }
+
+    #[test]
+    fn clone_while_reading() {
+        each_ip(&mut |addr| {
+            let accept = t!(TcpListener::bind(&addr));
+
+            // Enqueue a thread to write to a socket
+            let (tx, rx) = channel();
+            let (txdone, rxdone) = channel();
+            let txdone2 = txdone.clone();
+            let _t = thread::spawn(move|| {
+                let mut tcp = t!(TcpStream::connect(&addr));
+                rx.recv().unwrap();
+                t!(tcp.write(&[0]));
+                txdone2.send(()).unwrap();
+            });
+
+            // Spawn off a reading clone
+            let tcp = t!(accept.accept()).0;
+            let tcp2 = t!(tcp.try_clone());
+            let txdone3 = txdone.clone();
+            let _t = thread::spawn(move|| {
+                let mut tcp2 = tcp2;
+                t!(tcp2.read(&mut [0]));
+                txdone3.send(()).unwrap();
+            });
+
+            // Try to ensure that the reading clone is indeed reading
+            for _ in 0..50 {
+                thread::yield_now();
+            }
+
+            // clone the handle again while it's reading, then let it finish the
+            // read.
+            let _ = t!(tcp.try_clone());
+            tx.send(()).unwrap();
+            rxdone.recv().unwrap();
+            rxdone.recv().unwrap();
+        })
+    }
+
+    #[test]
+    fn clone_accept_smoke() {
+        each_ip(&mut |addr| {
+            let a = t!(TcpListener::bind(&addr));
+            let a2 = t!(a.try_clone());
+
+            let _t = thread::spawn(move|| {
+                let _ = TcpStream::connect(&addr);
+            });
+            let _t = thread::spawn(move|| {
+                let _ = TcpStream::connect(&addr);
+            });
+
+            t!(a.accept());
+            t!(a2.accept());
+        })
+    }
+
+    #[test]
+    fn clone_accept_concurrent() {
+        each_ip(&mut |addr| {
+            let a = t!(TcpListener::bind(&addr));
+            let a2 = t!(a.try_clone());
+
+            let (tx, rx) = channel();
+            let tx2 = tx.clone();
+
+            let _t = thread::spawn(move|| {
+                tx.send(t!(a.accept())).unwrap();
+            });
+            let _t = thread::spawn(move|| {
+                tx2.send(t!(a2.accept())).unwrap();
+            });
+
+            let _t = thread::spawn(move|| {
+                let _ = TcpStream::connect(&addr);
+            });
+            let _t = thread::spawn(move|| {
+                let _ = TcpStream::connect(&addr);
+            });
+
+            rx.recv().unwrap();
+            rx.recv().unwrap();
+        })
+    }
+
+    #[test]
+    fn debug() {
+        let name = "socket";
+        let socket_addr = next_test_ip4();
+
+        let listener = t!(TcpListener::bind(&socket_addr));
+        let listener_inner = listener.0.socket().as_inner();
+        let compare = format!("TcpListener {{ addr: {:?}, {}: {:?} }}",
+                              socket_addr, name, listener_inner);
+        assert_eq!(format!("{:?}", listener), compare);
+
+        let stream = t!(TcpStream::connect(&("localhost",
+                                                 socket_addr.port())));
+        let stream_inner = stream.0.socket().as_inner();
+        let compare = format!("TcpStream {{ addr: {:?}, \
+                              peer: {:?}, {}: {:?} }}",
+                              stream.local_addr().unwrap(),
+                              stream.peer_addr().unwrap(),
+                              name,
+                              stream_inner);
+        assert_eq!(format!("{:?}", stream), compare);
+    }
+
+    #[test]
+    fn timeouts() {
+        let addr = next_test_ip4();
+        let listener = t!(TcpListener::bind(&addr));
+
+        let stream = t!(TcpStream::connect(&("localhost", addr.port())));
+        let dur = Duration::new(15410, 0);
+
+        assert_eq!(None, t!(stream.read_timeout()));
+
+        t!(stream.set_read_timeout(Some(dur)));
+        assert_eq!(Some(dur), t!(stream.read_timeout()));
+
+        assert_eq!(None, t!(stream.write_timeout()));
+
+        t!(stream.set_write_timeout(Some(dur)));
+        assert_eq!(Some(dur), t!(stream.write_timeout()));
+
+        t!(stream.set_read_timeout(None));
+        assert_eq!(None, t!(stream.read_timeout()));
+
+        t!(stream.set_write_timeout(None));
+        assert_eq!(None, t!(stream.write_timeout()));
+        drop(listener);
+    }
+
+    #[test]
+    fn test_read_timeout() {
+        let addr = next_test_ip4();
+        let listener = t!(TcpListener::bind(&addr));
+
+        let mut stream = t!(TcpStream::connect(&("localhost", addr.port())));
+        t!(stream.set_read_timeout(Some(Duration::from_millis(1000))));
+
+        let mut buf = [0; 10];
+        let start = Instant::now();
+        let kind = stream.read(&mut buf).err().expect("expected error").kind();
+        assert!(kind == ErrorKind::WouldBlock || kind == ErrorKind::TimedOut);
+        assert!(start.elapsed() > Duration::from_millis(400));
+        drop(listener);
+    }
+
+    #[test]
+    fn test_read_with_timeout() {
+        let addr = next_test_ip4();
+        let listener = t!(TcpListener::bind(&addr));
+
+        let mut stream = t!(TcpStream::connect(&("localhost", addr.port())));
+        t!(stream.set_read_timeout(Some(Duration::from_millis(1000))));
+
+        let mut other_end = t!(listener.accept()).0;
+        t!(other_end.write_all(b"hello world"));
+
+        let mut buf = [0; 11];
+        t!(stream.read(&mut buf));
+        assert_eq!(b"hello world", &buf[..]);
+
+        let start = Instant::now();
+        let kind = stream.read(&mut buf).err().expect("expected error").kind();
+        assert!(kind == ErrorKind::WouldBlock || kind == ErrorKind::TimedOut);
+        assert!(start.elapsed() > Duration::from_millis(400));
+        drop(listener);
+    }
+
+    // Ensure the `set_read_timeout` and `set_write_timeout` calls return errors
+    // when passed zero Durations
+    #[test]
+    fn test_timeout_zero_duration() {
+        let addr = next_test_ip4();
+
+        let listener = t!(TcpListener::bind(&addr));
+        let stream = t!(TcpStream::connect(&addr));
+
+        let result = stream.set_write_timeout(Some(Duration::new(0, 0)));
+        let err = result.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+        let result = stream.set_read_timeout(Some(Duration::new(0, 0)));
+        let err = result.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+        drop(listener);
+    }
+
+    #[test]
+    fn nodelay() {
+        let addr = next_test_ip4();
+        let _listener = t!(TcpListener::bind(&addr));
+
+        let stream = t!(TcpStream::connect(&("localhost", addr.port())));
+
+        assert_eq!(false, t!(stream.nodelay()));
+        t!(stream.set_nodelay(true));
+        assert_eq!(true, t!(stream.nodelay()));
+        t!(stream.set_nodelay(false));
+        assert_eq!(false, t!(stream.nodelay()));
+    }
+
+    #[test]
+    fn tcp_info() {
+        each_ip(&mut |addr| {
+            let listener = t!(TcpListener::bind(&addr));
+            let mut stream = t!(TcpStream::connect(&addr));
+            let (mut accepted, _) = t!(listener.accept());
+
+            t!(stream.write_all(b"hello"));
+            let mut buf = [0; 5];
+            t!(accepted.read_exact(&mut buf));
+
+            let info = t!(stream.tcp_info());
+            assert!(info.mss > 0);
+            assert!(info.cwnd > 0);
+        })
+    }
+
+    #[test]
+    fn dont_fragment_and_path_mtu() {
+        let addr = next_test_ip4();
+        let listener = t!(TcpListener::bind(&addr));
+        let stream = t!(TcpStream::connect(&addr));
+        let _accepted = t!(listener.accept());
+
+        t!(stream.set_dont_fragment(true));
+        assert!(t!(stream.dont_fragment()));
+        t!(stream.set_dont_fragment(false));
+        assert!(!t!(stream.dont_fragment()));
+        assert!(t!(stream.path_mtu()) > 0);
+    }
+
+    #[test]
+    fn tos_and_tclass() {
+        // Whether the value reads back depends on the host's
+        // `DisableUserTOSSetting`, so only the calls themselves are checked.
+        let addr = next_test_ip4();
+        let listener = t!(TcpListener::bind(&addr));
+        t!(listener.set_tos_v4(Dscp::EF));
+        t!(listener.tos_v4());
+
+        let stream = t!(TcpStream::connect(&addr));
+        t!(stream.set_tos_v4(Dscp::EF));
+        t!(stream.tos_v4());
+
+        let addr = next_test_ip6();
+        let listener = t!(TcpListener::bind(&addr));
+        t!(listener.set_tclass_v6(Dscp::EF));
+        t!(listener.tclass_v6());
+
+        let stream = t!(TcpStream::connect(&addr));
+        t!(stream.set_tclass_v6(Dscp::EF));
+        t!(stream.tclass_v6());
+    }
+
+    #[test]
+    fn ttl() {
+        let ttl = 100;
+
+        let addr = next_test_ip4();
+        let listener = t!(TcpListener::bind(&addr));
+
+        t!(listener.set_ttl(ttl));
+        assert_eq!(ttl, t!(listener.ttl()));
+
+        let stream = t!(TcpStream::connect(&("localhost", addr.port())));
+
+        t!(stream.set_ttl(ttl));
+        assert_eq!(ttl, t!(stream.ttl()));
+    }
+
+    #[test]
+    fn builder() {
+        each_ip(&mut |addr| {
+            let listener = t!(TcpBuilder::new()
+                .reuse_address(true)
+                .send_buffer_size(64 * 1024)
+                .recv_buffer_size(64 * 1024)
+                .backlog(1)
+                .bind(&addr));
+            assert_eq!(addr, t!(listener.local_addr()));
+
+            let _t = thread::spawn(move || {
+                t!(TcpStream::connect(&addr));
+            });
+            t!(listener.accept());
+        })
+    }
+
+    #[test]
+    fn builder_exclusive_address_use() {
+        let addr = next_test_ip4();
+        let _listener = t!(TcpBuilder::new().exclusive_address_use(true).bind(&addr));
+        assert!(TcpBuilder::new().reuse_address(true).bind(&addr).is_err());
+    }
+
+    #[test]
+    fn builder_unsupported() {
+        let addr = next_test_ip4();
+        match TcpBuilder::new().reuse_port(true).bind(&addr) {
+            Err(ref e) if e.kind() == ErrorKind::InvalidInput => {}
+            Err(e) => panic!("unexpected error {}", e),
+            Ok(_) => panic!("unexpected success"),
+        }
+        match TcpBuilder::default().send_buffer_size(1 << 31).bind(&addr) {
+            Err(ref e) if e.kind() == ErrorKind::InvalidInput => {}
+            Err(e) => panic!("unexpected error {}", e),
+            Ok(_) => panic!("unexpected success"),
+        }
+    }
+
+    #[test]
+    fn builder_only_v6() {
+        let addr = next_test_ip6();
+        let listener = t!(TcpBuilder::new().only_v6(true).bind(&addr));
+        assert!(t!(listener.only_v6()));
+
+        assert!(TcpBuilder::new().only_v6(true).bind(&next_test_ip4()).is_err());
+    }
+
+    #[test]
+    fn keepalive() {
+        let addr = next_test_ip4();
+        let _listener = t!(TcpListener::bind(&addr));
+
+        let stream = t!(TcpStream::connect(&addr));
+        assert_eq!(None, t!(stream.keepalive()));
+
+        let params = KeepaliveParams {
+            idle: Duration::from_secs(30),
+            interval: Duration::from_secs(3),
+            count: 5,
+        };
+        t!(stream.set_keepalive(Some(params)));
+        assert_eq!(Some(params), t!(stream.keepalive()));
+
+        let subsec = KeepaliveParams { idle: Duration::from_millis(1500), ..params };
+        t!(stream.set_keepalive(Some(subsec)));
+        assert_eq!(Some(KeepaliveParams { idle: Duration::from_secs(2), ..params }),
+                   t!(stream.keepalive()));
+
+        t!(stream.set_keepalive(None));
+        assert_eq!(None, t!(stream.keepalive()));
+
+        let params = KeepaliveParams { idle: Duration::new(0, 0), ..params };
+        let err = stream.set_keepalive(Some(params)).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn happy_eyeballs_skips_unreachable() {
+        let dead = next_test_ip4();
+        let addr = next_test_ip4();
+        let _listener = t!(TcpListener::bind(&addr));
+
+        let opts = HappyEyeballsOpts {
+            attempt_delay: Duration::from_millis(10),
+            timeout: Some(Duration::from_secs(10)),
+        };
+        let stream = t!(TcpStream::connect_happy_eyeballs(&[dead, addr][..], &opts));
+        assert_eq!(addr, t!(stream.peer_addr()));
+    }
+
+    #[test]
+    fn happy_eyeballs_first_wins() {
+        // Neither listener ever accepts; the connections still complete.
+        let addr1 = next_test_ip6();
+        let addr2 = next_test_ip4();
+        let _listener1 = t!(TcpListener::bind(&addr1));
+        let _listener2 = t!(TcpListener::bind(&addr2));
+
+        let opts = HappyEyeballsOpts {
+            attempt_delay: Duration::from_secs(5),
+            timeout: None,
+        };
+        let start = Instant::now();
+        let stream = t!(TcpStream::connect_happy_eyeballs(&[addr1, addr2][..], &opts));
+        assert_eq!(addr1, t!(stream.peer_addr()));
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn happy_eyeballs_blackholed() {
+        // 192.0.2.0/24 is reserved for documentation (RFC 5737): a connection
+        // attempt to it is never answered, or fails at once without a route.
+        let blackhole = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1)), 80);
+        let addr = next_test_ip6();
+        let _listener = t!(TcpListener::bind(&addr));
+
+        let opts = HappyEyeballsOpts {
+            attempt_delay: Duration::from_millis(200),
+            timeout: Some(Duration::from_secs(10)),
+        };
+        let start = Instant::now();
+        let stream = t!(TcpStream::connect_happy_eyeballs(&[blackhole, addr][..], &opts));
+        assert_eq!(addr, t!(stream.peer_addr()));
+        // The IPv6 attempt starts after one attempt delay. The bound leaves
+        // plenty of slack for a loaded machine while still failing if the
+        // race waited for the blackholed attempt to time out, which takes
+        // Windows over 20 seconds.
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn happy_eyeballs_all_fail() {
+        let opts = HappyEyeballsOpts {
+            attempt_delay: Duration::from_millis(10),
+            timeout: None,
+        };
+        let addrs = [next_test_ip4(), next_test_ip6()];
+        match TcpStream::connect_happy_eyeballs(&addrs[..], &opts) {
+            Err(ref e) if e.kind() == ErrorKind::ConnectionRefused => {}
+            Err(e) => panic!("unexpected error {}", e),
+            Ok(_) => panic!("unexpected success"),
+        }
+
+        let addrs: [SocketAddr; 0] = [];
+        let e = TcpStream::connect_happy_eyeballs(&addrs[..], &opts).unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn linger() {
+        let addr = next_test_ip4();
+        let _listener = t!(TcpListener::bind(&addr));
+
+        let stream = t!(TcpStream::connect(&addr));
+        assert_eq!(None, t!(stream.linger()));
+
+        t!(stream.set_linger(Some(Duration::from_secs(5))));
+        assert_eq!(Some(Duration::from_secs(5)), t!(stream.linger()));
+
+        t!(stream.set_linger(Some(Duration::from_millis(1))));
+        assert_eq!(Some(Duration::from_secs(1)), t!(stream.linger()));
+
+        t!(stream.set_linger(None));
+        assert_eq!(None, t!(stream.linger()));
+    }
+
+    #[test]
+    fn close_abortive() {
+        let addr = next_test_ip4();
+        let listener = t!(TcpListener::bind(&addr));
+
+        let stream = t!(TcpStream::connect(&addr));
+        let (mut peer, _) = t!(listener.accept());
+        t!(stream.close_abortive());
+
+        let mut buf = [0; 10];
+        match peer.read(&mut buf) {
+            Err(ref e) if e.kind() == ErrorKind::ConnectionReset => {}
+            other => panic!("expected a connection reset, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn set_nonblocking() {
+        let addr = next_test_ip4();
+        let listener = t!(TcpListener::bind(&addr));
+
+        t!(listener.set_nonblocking(true));
+        t!(listener.set_nonblocking(false));
+
+        let mut stream = t!(TcpStream::connect(&("localhost", addr.port())));
+
+        t!(stream.set_nonblocking(false));
+        t!(stream.set_nonblocking(true));
+
+        let mut buf = [0];
+        match stream.read(&mut buf) {
+            Ok(_) => panic!("expected error"),
+            Err(ref e) if e.kind() == ErrorKind::WouldBlock => {}
+            Err(e) => panic!("unexpected error {}", e),
+        }
+    }
+
+    #[test]
+    fn peek() {
+        each_ip(&mut |addr| {
+            let (txdone, rxdone) = channel();
+
+            let srv = t!(TcpListener::bind(&addr));
+            let _t = thread::spawn(move|| {
+                let mut cl = t!(srv.accept()).0;
+                cl.write(&[1,3,3,7]).unwrap();
+                t!(rxdone.recv());
+            });
+
+            let mut c = t!(TcpStream::connect(&addr));
+            let mut b = [0; 10];
+            for _ in 1..3 {
+                let len = c.peek(&mut b).unwrap();
+                assert_eq!(len, 4);
+            }
+            let len = c.read(&mut b).unwrap();
+            assert_eq!(len, 4);
+
+            t!(c.set_nonblocking(true));
+            match c.peek(&mut b) {
+                Ok(_) => panic!("expected error"),
+                Err(ref e) if e.kind() == ErrorKind::WouldBlock => {}
+                Err(e) => panic!("unexpected error {}", e),
+            }
+            t!(txdone.send(()));
+        })
+    }
+
+    #[test]
+    fn vectored() {
+        each_ip(&mut |addr| {
+            let srv = t!(TcpListener::bind(&addr));
+            let _t = thread::spawn(move|| {
+                let cl = t!(srv.accept()).0;
+                t!(cl.write_vectored(&[b"hello", b" ", b"world"]));
+            });
+
+            let mut c = t!(TcpStream::connect(&addr));
+            let mut buf = vec![];
+            t!(c.read_to_end(&mut buf));
+            assert_eq!(b"hello world", &buf[..]);
+        })
+    }
+
+    #[test]
+    fn connect_timeout_unroutable() {
+        // this IP is unroutable, so connections should always time out,
+        // provided the network is reachable to begin with.
+        let addr = "10.255.255.1:80".parse().unwrap();
+        let e = TcpStream::connect_timeout(&addr, Duration::from_millis(250)).unwrap_err();
+        assert!(e.kind() == io::ErrorKind::TimedOut ||
+                e.kind() == io::ErrorKind::Other,
+                "bad error: {} {:?}", e, e.kind());
+    }
+
+    #[test]
+    fn connect_timeout_unbound() {
+        // bind and drop a socket to track down a "probably unassigned" port
+        let socket = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = socket.local_addr().unwrap();
+        drop(socket);
+
+        let timeout = Duration::from_secs(1);
+        let e = TcpStream::connect_timeout(&addr, timeout).unwrap_err();
+        assert!(e.kind() == io::ErrorKind::ConnectionRefused ||
+                e.kind() == io::ErrorKind::TimedOut ||
+                e.kind() == io::ErrorKind::Other,
+                "bad error: {} {:?}", e, e.kind());
+    }
+
+    #[test]
+    fn connect_timeout_valid() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        TcpStream::connect_timeout(&addr, Duration::from_secs(2)).unwrap();
+    }
+}
@@ -0,0 +1,1927 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::fmt;
+use std::io::{self, Error, ErrorKind};
+use net::{ToSocketAddrs, SocketAddr, IpAddr, Ipv4Addr, Ipv6Addr, Dscp};
+use sys_common::net as net_imp;
+use sys_common::{AsInner, FromInner, IntoInner};
+use std::time::{Duration, SystemTime};
+
+/// A UDP socket.
+///
+/// After creating a `UdpSocket` by [`bind`]ing it to a socket address, data can be
+/// [sent to] and [received from] any other socket address.
+///
+/// Although UDP is a connectionless protocol, this implementation provides an interface
+/// to set an address where data should be sent and received from. After setting a remote
+/// address with [`connect`], data can be sent to and received from that address with
+/// [`send`] and [`recv`].
+///
+/// As stated in the User Datagram Protocol's specification in [IETF RFC 768], UDP is
+/// an unordered, unreliable protocol; refer to [`TcpListener`] and [`TcpStream`] for TCP
+/// primitives.
+///
+/// [`bind`]: #method.bind
+/// [`connect`]: #method.connect
+/// [IETF RFC 768]: https://tools.ietf.org/html/rfc768
+/// [`recv`]: #method.recv
+/// [received from]: #method.recv_from
+/// [`send`]: #method.send
+/// [sent to]: #method.send_to
+/// [`TcpListener`]: ../../std/net/struct.TcpListener.html
+/// [`TcpStream`]: ../../std/net/struct.TcpStream.html
+///
+/// # Examples
+///
+/// ```ignore
+/// use std::net::UdpSocket;
+///
+/// # fn foo() -> std::io::Result<()> {
+/// {
+///     let mut socket = UdpSocket::bind("127.0.0.1:34254")?;
+///
+///     // Receives a single datagram message on the socket. If `buf` is too small to hold
+///     // the message, it will be cut off.
+///     let mut buf = [0; 10];
+///     let (amt, src) = socket.recv_from(&mut buf)?;
+///
+///     // Redeclare `buf` as slice of the received data and send reverse data back to origin.
+///     let buf = &mut buf[..amt];
+///     buf.reverse();
+///     socket.send_to(buf, &src)?;
+///     // close the socket
+/// }
+/// # Ok(())
+/// # }
+/// ```
+// #[stable(feature = "rust1", since = "1.0.0")]
+pub struct UdpSocket(net_imp::UdpSocket);
+
+/// The local end of a datagram, as received by
+/// [`recv_msg`](struct.UdpSocket.html#method.recv_msg) or passed to
+/// [`send_msg`](struct.UdpSocket.html#method.send_msg).
+///
+/// On a socket bound to a wildcard address, `local_addr` is the address the
+/// datagram was sent to (or should be sent from), and `interface` is the index
+/// of the interface it arrived on (or should leave through). An `interface` of
+/// 0 on send leaves the choice of interface to the routing table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PacketInfo {
+    /// The local address of the datagram.
+    pub local_addr: IpAddr,
+    /// The index of the interface the datagram arrived on or is sent through.
+    pub interface: u32,
+}
+
+/// What [`recv_event`](struct.UdpSocket.html#method.recv_event) received.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum UdpEvent {
+    /// A datagram of the given length arrived from the given address.
+    Datagram(usize, SocketAddr),
+    /// An earlier datagram drew an ICMP port-unreachable message from the
+    /// given peer, or from an unknown peer if the platform doesn't say which.
+    Unreachable(Option<SocketAddr>),
+}
+
+impl UdpSocket {
+    /// Creates a UDP socket from the given address.
+    ///
+    /// The address type can be any implementor of [`ToSocketAddrs`] trait. See
+    /// its documentation for concrete examples.
+    ///
+    /// If `addr` yields multiple addresses, `bind` will be attempted with
+    /// each of the addresses until one succeeds and returns the socket. If none
+    /// of the addresses succeed in creating a socket, the error returned from
+    /// the last attempt (the last address) is returned.
+    ///
+    /// [`ToSocketAddrs`]: ../../std/net/trait.ToSocketAddrs.html
+    ///
+    /// # Examples
+    ///
+    /// Create a UDP socket bound to `127.0.0.1:3400`:
+    ///
+    /// ```ignore
+    /// use std::net::UdpSocket;
+    ///
+    /// let socket = UdpSocket::bind("127.0.0.1:3400").expect("couldn't bind to address");
+    /// ```
+    // #[stable(feature = "rust1", since = "1.0.0")]
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> io::Result<UdpSocket> {
+        super::each_addr(addr, net_imp::UdpSocket::bind).map(UdpSocket)
+    }
+
+    /// Receives a single datagram message on the socket. On success, returns the number
+    /// of bytes read and the origin.
+    ///
+    /// The function must be called with valid byte array `buf` of sufficient size to
+    /// hold the message bytes. If a message is too long to fit in the supplied buffer,
+    /// excess bytes may be discarded.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::UdpSocket;
+    ///
+    /// let socket = UdpSocket::bind("127.0.0.1:34254").expect("couldn't bind to address");
+    /// let mut buf = [0; 10];
+    /// let (number_of_bytes, src_addr) = socket.recv_from(&mut buf)
+    ///                                         .expect("Didn't receive data");
+    /// let filled_buf = &mut buf[..number_of_bytes];
+    /// ```
+    // #[stable(feature = "rust1", since = "1.0.0")]
+    pub fn recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
+        self.0.recv_from(buf)
+    }
+
+    /// Receives a single datagram message on the socket, without removing it from the
+    /// queue. On success, returns the number of bytes read and the origin.
+    ///
+    /// The function must be called with valid byte array `buf` of sufficient size to
+    /// hold the message bytes. If a message is too long to fit in the supplied buffer,
+    /// excess bytes may be discarded.
+    ///
+    /// Successive calls return the same data. This is accomplished by passing
+    /// `MSG_PEEK` as a flag to the underlying `recvfrom` system call.
+    ///
+    /// Do not use this function to implement busy waiting, instead use `libc::poll` to
+    /// synchronize IO events on one or more sockets.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::UdpSocket;
+    ///
+    /// let socket = UdpSocket::bind("127.0.0.1:34254").expect("couldn't bind to address");
+    /// let mut buf = [0; 10];
+    /// let (number_of_bytes, src_addr) = socket.peek_from(&mut buf)
+    ///                                         .expect("Didn't receive data");
+    /// let filled_buf = &mut buf[..number_of_bytes];
+    /// ```
+    // #[stable(feature = "peek", since = "1.18.0")]
+    pub fn peek_from(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
+        self.0.peek_from(buf)
+    }
+
+    /// Sets whether the system records the time each datagram is received
+    /// at, for [`recv_from_with_timestamp`] to return.
+    ///
+    /// This sets the `SIO_TIMESTAMPING` control code, which needs Windows 10
+    /// version 2004 or later. The timestamps are taken by the network stack
+    /// as the datagram arrives, not when it is read. Windows only timestamps
+    /// datagram sockets, so there is no counterpart on [`TcpStream`].
+    ///
+    /// The stack records each timestamp as a performance counter value, which
+    /// is turned into a `SystemTime` by comparing it with the current reading
+    /// of the counter and of the system clock. The result is approximate, and
+    /// moves with any adjustment of the system clock made in between.
+    ///
+    /// [`TcpStream`]: struct.TcpStream.html
+    ///
+    /// [`recv_from_with_timestamp`]: #method.recv_from_with_timestamp
+    pub fn set_recv_timestamps(&self, recv: bool) -> io::Result<()> {
+        self.0.set_recv_timestamps(recv)
+    }
+
+    /// Receives a single datagram message on the socket, along with the time
+    /// it was received at. On success, returns the number of bytes read, the
+    /// origin and the receive time.
+    ///
+    /// The receive time is only available once it has been enabled with
+    /// [`set_recv_timestamps`]; until then it is `None`.
+    ///
+    /// [`set_recv_timestamps`]: #method.set_recv_timestamps
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::UdpSocket;
+    /// use std::time::SystemTime;
+    ///
+    /// let socket = UdpSocket::bind("127.0.0.1:34254").expect("couldn't bind to address");
+    /// socket.set_recv_timestamps(true).expect("set_recv_timestamps call failed");
+    /// let mut buf = [0; 10];
+    /// let (_, _, received) = socket.recv_from_with_timestamp(&mut buf)
+    ///                              .expect("Didn't receive data");
+    /// if let Some(received) = received {
+    ///     println!("read {:?} after arrival", SystemTime::now().duration_since(received));
+    /// }
+    /// ```
+    pub fn recv_from_with_timestamp(&self, buf: &mut [u8])
+                                    -> io::Result<(usize, SocketAddr, Option<SystemTime>)> {
+        self.0.recv_from_with_timestamp(buf)
+    }
+
+    /// Receives a single datagram message on the socket, or an event for a
+    /// peer that turned out to be unreachable.
+    ///
+    /// A datagram sent with [`send_to`] to a port nobody is listening on draws
+    /// an ICMP port-unreachable message, which [`recv_from`] reports as a
+    /// [`ConnectionReset`] error on Windows. That error says nothing about
+    /// which peer it came from and is easily mistaken for a failure of the
+    /// socket itself; `recv_event` returns it as an [`UdpEvent::Unreachable`]
+    /// for the peer instead, so the socket can go on serving other peers.
+    ///
+    /// [`send_to`]: #method.send_to
+    /// [`recv_from`]: #method.recv_from
+    /// [`ConnectionReset`]: ../../std/io/enum.ErrorKind.html#variant.ConnectionReset
+    /// [`UdpEvent::Unreachable`]: enum.UdpEvent.html#variant.Unreachable
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::{UdpEvent, UdpSocket};
+    ///
+    /// let socket = UdpSocket::bind("127.0.0.1:34254").expect("couldn't bind to address");
+    /// let mut buf = [0; 10];
+    /// loop {
+    ///     match socket.recv_event(&mut buf).expect("Didn't receive data") {
+    ///         UdpEvent::Datagram(amt, src) => socket.send_to(&buf[..amt], &src).map(|_| ()),
+    ///         UdpEvent::Unreachable(peer) => Ok(println!("{:?} went away", peer)),
+    ///     }.expect("couldn't send data");
+    /// }
+    /// ```
+    pub fn recv_event(&self, buf: &mut [u8]) -> io::Result<UdpEvent> {
+        self.0.recv_event(buf)
+    }
+
+    /// Sets whether an ICMP port-unreachable message for an earlier datagram
+    /// is reported by the next receive on this socket.
+    ///
+    /// This sets the `SIO_UDP_CONNRESET` control code. It is on by default;
+    /// turning it off makes [`recv_from`] ignore unreachable peers, and
+    /// [`recv_event`] never report them.
+    ///
+    /// [`recv_from`]: #method.recv_from
+    /// [`recv_event`]: #method.recv_event
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::UdpSocket;
+    ///
+    /// let socket = UdpSocket::bind("127.0.0.1:34254").expect("couldn't bind to address");
+    /// socket.set_connreset(false).expect("set_connreset call failed");
+    /// ```
+    pub fn set_connreset(&self, report: bool) -> io::Result<()> {
+        self.0.set_connreset(report)
+    }
+
+    /// Receives several datagram messages on the socket in one call. On
+    /// success, returns the number of bytes read and the origin of each
+    /// datagram, which were read into the leading elements of `bufs`.
+    ///
+    /// The call blocks until at least one datagram is available, unless the
+    /// socket is nonblocking, then keeps receiving for as long as more are
+    /// queued, up to one per buffer. A datagram after the first that can't be
+    /// received, for instance because it doesn't fit its buffer, ends the
+    /// batch and is left queued, so the next receive reports the error.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::UdpSocket;
+    ///
+    /// let socket = UdpSocket::bind("127.0.0.1:34254").expect("couldn't bind to address");
+    /// let mut bufs = vec![[0; 1500]; 64];
+    /// let mut slices: Vec<&mut [u8]> = bufs.iter_mut().map(|b| &mut b[..]).collect();
+    /// let msgs = socket.recv_batch(&mut slices).expect("Didn't receive data");
+    /// for (&(amt, src), buf) in msgs.iter().zip(&slices) {
+    ///     println!("{} bytes from {}: {:?}", amt, src, &buf[..amt]);
+    /// }
+    /// ```
+    pub fn recv_batch(&self, bufs: &mut [&mut [u8]])
+                      -> io::Result<Vec<(usize, SocketAddr)>> {
+        self.0.recv_batch(bufs)
+    }
+
+    /// Sends each buffer in `bufs` as a datagram to the address at the same
+    /// position in `addrs`. On success, returns the number of datagrams sent.
+    ///
+    /// `bufs` and `addrs` must be the same length. If an error occurs after
+    /// the first datagram, the number sent so far is returned instead; the
+    /// datagram that failed was not sent, and sending it again reports the
+    /// error.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::{SocketAddr, UdpSocket};
+    ///
+    /// let socket = UdpSocket::bind("127.0.0.1:34254").expect("couldn't bind to address");
+    /// let dst: SocketAddr = "127.0.0.1:4242".parse().unwrap();
+    /// let sent = socket.send_batch(&[b"one", b"two"], &[dst, dst])
+    ///                  .expect("couldn't send data");
+    /// ```
+    pub fn send_batch(&self, bufs: &[&[u8]], addrs: &[SocketAddr]) -> io::Result<usize> {
+        if bufs.len() != addrs.len() {
+            return Err(Error::new(ErrorKind::InvalidInput,
+                                  "number of buffers and addresses differ"))
+        }
+        self.0.send_batch(bufs, addrs)
+    }
+
+    /// Receives a single datagram message on the socket, along with the local
+    /// address it was sent to and the interface it arrived on. On success,
+    /// returns the number of bytes read, the origin and the packet info.
+    ///
+    /// The packet info is only available once it has been enabled with
+    /// [`set_recv_packet_info`]; until then it is `None`.
+    ///
+    /// As with [`recv_from`], a datagram too long to fit in `buf` is an
+    /// error, and its remaining bytes are discarded.
+    ///
+    /// [`recv_from`]: #method.recv_from
+    ///
+    /// [`set_recv_packet_info`]: #method.set_recv_packet_info
+    ///
+    /// # Examples
+    ///
+    /// Reply to a client from the address it sent its request to:
+    ///
+    /// ```ignore
+    /// use std::net::UdpSocket;
+    ///
+    /// let socket = UdpSocket::bind("0.0.0.0:34254").expect("couldn't bind to address");
+    /// socket.set_recv_packet_info(true).expect("set_recv_packet_info call failed");
+    /// let mut buf = [0; 10];
+    /// let (amt, src, info) = socket.recv_msg(&mut buf).expect("Didn't receive data");
+    /// if let Some(info) = info {
+    ///     socket.send_msg(&buf[..amt], &src, &info).expect("couldn't send data");
+    /// }
+    /// ```
+    pub fn recv_msg(&self, buf: &mut [u8])
+                    -> io::Result<(usize, SocketAddr, Option<PacketInfo>)> {
+        self.0.recv_msg(buf)
+    }
+
+    /// Sends data on the socket to the given address, from the local address
+    /// and through the interface in `info`. On success, returns the number of
+    /// bytes written.
+    ///
+    /// The local address must be one the socket could have been bound to,
+    /// which in practice means the socket is bound to a wildcard address.
+    pub fn send_msg(&self, buf: &[u8], addr: &SocketAddr, info: &PacketInfo)
+                    -> io::Result<usize> {
+        self.0.send_msg(buf, addr, info)
+    }
+
+    /// Sets whether datagrams received with [`recv_msg`] carry their
+    /// [`PacketInfo`].
+    ///
+    /// This sets the `IP_PKTINFO` or `IPV6_PKTINFO` option, depending on the
+    /// family of the socket. A dual-stack IPv6 socket gets both.
+    ///
+    /// [`recv_msg`]: #method.recv_msg
+    /// [`PacketInfo`]: struct.PacketInfo.html
+    pub fn set_recv_packet_info(&self, recv: bool) -> io::Result<()> {
+        self.0.set_recv_packet_info(recv)
+    }
+
+    /// Gets whether datagrams received with [`recv_msg`] carry their
+    /// [`PacketInfo`].
+    ///
+    /// For more information about this option, see [`set_recv_packet_info`].
+    ///
+    /// [`recv_msg`]: #method.recv_msg
+    /// [`PacketInfo`]: struct.PacketInfo.html
+    /// [`set_recv_packet_info`]: #method.set_recv_packet_info
+    pub fn recv_packet_info(&self) -> io::Result<bool> {
+        self.0.recv_packet_info()
+    }
+
+    /// Sends data on the socket to the given address. On success, returns the
+    /// number of bytes written.
+    ///
+    /// Address type can be any implementor of [`ToSocketAddrs`] trait. See its
+    /// documentation for concrete examples.
+    ///
+    /// It is possible for `addr` to yield multiple addresses, but `send_to`
+    /// will only send data to the first address yielded by `addr`.
+    ///
+    /// This will return an error when the IP version of the local socket
+    /// does not match that returned from [`ToSocketAddrs`].
+    ///
+    /// See https://github.com/rust-lang/rust/issues/34202 for more details.
+    ///
+    /// [`ToSocketAddrs`]: ../../std/net/trait.ToSocketAddrs.html
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::UdpSocket;
+    ///
+    /// let socket = UdpSocket::bind("127.0.0.1:34254").expect("couldn't bind to address");
+    /// socket.send_to(&[0; 10], "127.0.0.1:4242").expect("couldn't send data");
+    /// ```
+    // #[stable(feature = "rust1", since = "1.0.0")]
+    pub fn send_to<A: ToSocketAddrs>(&self, buf: &[u8], addr: A)
+                                     -> io::Result<usize> {
+        match addr.to_socket_addrs()?.next() {
+            Some(addr) => self.0.send_to(buf, &addr),
+            None => Err(Error::new(ErrorKind::InvalidInput,
+                                   "no addresses to send data to")),
+        }
+    }
+
+    /// Returns the socket address that this socket was created from.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4, UdpSocket};
+    ///
+    /// let socket = UdpSocket::bind("127.0.0.1:34254").expect("couldn't bind to address");
+    /// assert_eq!(socket.local_addr().unwrap(),
+    ///            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(127, 0, 0, 1), 34254)));
+    /// ```
+    // #[stable(feature = "rust1", since = "1.0.0")]
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.0.socket_addr()
+    }
+
+    /// Creates a new independently owned handle to the underlying socket.
+    ///
+    /// The returned `UdpSocket` is a reference to the same socket that this
+    /// object references. Both handles will read and write the same port, and
+    /// options set on one socket will be propagated to the other.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::UdpSocket;
+    ///
+    /// let socket = UdpSocket::bind("127.0.0.1:34254").expect("couldn't bind to address");
+    /// let socket_clone = socket.try_clone().expect("couldn't clone the socket");
+    /// ```
+    // #[stable(feature = "rust1", since = "1.0.0")]
+    pub fn try_clone(&self) -> io::Result<UdpSocket> {
+        self.0.duplicate().map(UdpSocket)
+    }
+
+    /// Sets the read timeout to the timeout specified.
+    ///
+    /// If the value specified is [`None`], then [`read`] calls will block
+    /// indefinitely. An [`Err`] is returned if the zero [`Duration`] is
+    /// passed to this method.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// Platforms may return a different error code whenever a read times out as
+    /// a result of setting this option. For example Unix typically returns an
+    /// error of the kind [`WouldBlock`], but Windows may return [`TimedOut`].
+    ///
+    /// [`None`]: ../../std/option/enum.Option.html#variant.None
+    /// [`Err`]: ../../std/result/enum.Result.html#variant.Err
+    /// [`read`]: ../../std/io/trait.Read.html#tymethod.read
+    /// [`Duration`]: ../../std/time/struct.Duration.html
+    /// [`WouldBlock`]: ../../std/io/enum.ErrorKind.html#variant.WouldBlock
+    /// [`TimedOut`]: ../../std/io/enum.ErrorKind.html#variant.TimedOut
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::UdpSocket;
+    ///
+    /// let socket = UdpSocket::bind("127.0.0.1:34254").expect("couldn't bind to address");
+    /// socket.set_read_timeout(None).expect("set_read_timeout call failed");
+    /// ```
+    // #[stable(feature = "socket_timeout", since = "1.4.0")]
+    pub fn set_read_timeout(&self, dur: Option<Duration>) -> io::Result<()> {
+        self.0.set_read_timeout(dur)
+    }
+
+    /// Sets the write timeout to the timeout specified.
+    ///
+    /// If the value specified is [`None`], then [`write`] calls will block
+    /// indefinitely. An [`Err`] is returned if the zero [`Duration`] is
+    /// passed to this method.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// Platforms may return a different error code whenever a write times out
+    /// as a result of setting this option. For example Unix typically returns
+    /// an error of the kind [`WouldBlock`], but Windows may return [`TimedOut`].
+    ///
+    /// [`None`]: ../../std/option/enum.Option.html#variant.None
+    /// [`Err`]: ../../std/result/enum.Result.html#variant.Err
+    /// [`write`]: ../../std/io/trait.Write.html#tymethod.write
+    /// [`Duration`]: ../../std/time/struct.Duration.html
+    /// [`WouldBlock`]: ../../std/io/enum.ErrorKind.html#variant.WouldBlock
+    /// [`TimedOut`]: ../../std/io/enum.ErrorKind.html#variant.TimedOut
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::UdpSocket;
+    ///
+    /// let socket = UdpSocket::bind("127.0.0.1:34254").expect("couldn't bind to address");
+    /// socket.set_write_timeout(None).expect("set_write_timeout call failed");
+    /// ```
+    // #[stable(feature = "socket_timeout", since = "1.4.0")]
+    pub fn set_write_timeout(&self, dur: Option<Duration>) -> io::Result<()> {
+        self.0.set_write_timeout(dur)
+    }
+
+    /// Returns the read timeout of this socket.
+    ///
+    /// If the timeout is [`None`], then [`read`] calls will block indefinitely.
+    ///
+    /// [`None`]: ../../std/option/enum.Option.html#variant.None
+    /// [`read`]: ../../std/io/trait.Read.html#tymethod.read
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::UdpSocket;
+    ///
+    /// let socket = UdpSocket::bind("127.0.0.1:34254").expect("couldn't bind to address");
+    /// socket.set_read_timeout(None).expect("set_read_timeout call failed");
+    /// assert_eq!(socket.read_timeout().unwrap(), None);
+    /// ```
+    // #[stable(feature = "socket_timeout", since = "1.4.0")]
+    pub fn read_timeout(&self) -> io::Result<Option<Duration>> {
+        self.0.read_timeout()
+    }
+
+    /// Returns the write timeout of this socket.
+    ///
+    /// If the timeout is [`None`], then [`write`] calls will block indefinitely.
+    ///
+    /// [`None`]: ../../std/option/enum.Option.html#variant.None
+    /// [`write`]: ../../std/io/trait.Write.html#tymethod.write
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::UdpSocket;
+    ///
+    /// let socket = UdpSocket::bind("127.0.0.1:34254").expect("couldn't bind to address");
+    /// socket.set_write_timeout(None).expect("set_write_timeout call failed");
+    /// assert_eq!(socket.write_timeout().unwrap(), None);
+    /// ```
+    // #[stable(feature = "socket_timeout", since = "1.4.0")]
+    pub fn write_timeout(&self) -> io::Result<Option<Duration>> {
+        self.0.write_timeout()
+    }
+
+    /// Sets the value of the `SO_BROADCAST` option for this socket.
+    ///
+    /// When enabled, this socket is allowed to send packets to a broadcast
+    /// address.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::UdpSocket;
+    ///
+    /// let socket = UdpSocket::bind("127.0.0.1:34254").expect("couldn't bind to address");
+    /// socket.set_broadcast(false).expect("set_broadcast call failed");
+    /// ```
+    // #[stable(feature = "net2_mutators", since = "1.9.0")]
+    pub fn set_broadcast(&self, broadcast: bool) -> io::Result<()> {
+        self.0.set_broadcast(broadcast)
+    }
+
+    /// Gets the value of the `SO_BROADCAST` option for this socket.
+    ///
+    /// For more information about this option, see
+    /// [`set_broadcast`][link].
+    ///
+    /// [link]: #method.set_broadcast
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::UdpSocket;
+    ///
+    /// let socket = UdpSocket::bind("127.0.0.1:34254").expect("couldn't bind to address");
+    /// socket.set_broadcast(false).expect("set_broadcast call failed");
+    /// assert_eq!(socket.broadcast().unwrap(), false);
+    /// ```
+    // #[stable(feature = "net2_mutators", since = "1.9.0")]
+    pub fn broadcast(&self) -> io::Result<bool> {
+        self.0.broadcast()
+    }
+
+    /// Sets the value of the `IP_MULTICAST_LOOP` option for this socket.
+    ///
+    /// If enabled, multicast packets will be looped back to the local socket.
+    /// Note that this may not have any effect on IPv6 sockets.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::UdpSocket;
+    ///
+    /// let socket = UdpSocket::bind("127.0.0.1:34254").expect("couldn't bind to address");
+    /// socket.set_multicast_loop_v4(false).expect("set_multicast_loop_v4 call failed");
+    /// ```
+    // #[stable(feature = "net2_mutators", since = "1.9.0")]
+    pub fn set_multicast_loop_v4(&self, multicast_loop_v4: bool) -> io::Result<()> {
+        self.0.set_multicast_loop_v4(multicast_loop_v4)
+    }
+
+    /// Gets the value of the `IP_MULTICAST_LOOP` option for this socket.
+    ///
+    /// For more information about this option, see
+    /// [`set_multicast_loop_v4`][link].
+    ///
+    /// [link]: #method.set_multicast_loop_v4
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::UdpSocket;
+    ///
+    /// let socket = UdpSocket::bind("127.0.0.1:34254").expect("couldn't bind to address");
+    /// socket.set_multicast_loop_v4(false).expect("set_multicast_loop_v4 call failed");
+    /// assert_eq!(socket.multicast_loop_v4().unwrap(), false);
+    /// ```
+    // #[stable(feature = "net2_mutators", since = "1.9.0")]
+    pub fn multicast_loop_v4(&self) -> io::Result<bool> {
+        self.0.multicast_loop_v4()
+    }
+
+    /// Sets the value of the `IP_MULTICAST_TTL` option for this socket.
+    ///
+    /// Indicates the time-to-live value of outgoing multicast packets for
+    /// this socket. The default value is 1 which means that multicast packets
+    /// don't leave the local network unless explicitly requested.
+    ///
+    /// Note that this may not have any effect on IPv6 sockets.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::UdpSocket;
+    ///
+    /// let socket = UdpSocket::bind("127.0.0.1:34254").expect("couldn't bind to address");
+    /// socket.set_multicast_ttl_v4(42).expect("set_multicast_ttl_v4 call failed");
+    /// ```
+    // #[stable(feature = "net2_mutators", since = "1.9.0")]
+    pub fn set_multicast_ttl_v4(&self, multicast_ttl_v4: u32) -> io::Result<()> {
+        self.0.set_multicast_ttl_v4(multicast_ttl_v4)
+    }
+
+    /// Gets the value of the `IP_MULTICAST_TTL` option for this socket.
+    ///
+    /// For more information about this option, see
+    /// [`set_multicast_ttl_v4`][link].
+    ///
+    /// [link]: #method.set_multicast_ttl_v4
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::UdpSocket;
+    ///
+    /// let socket = UdpSocket::bind("127.0.0.1:34254").expect("couldn't bind to address");
+    /// socket.set_multicast_ttl_v4(42).expect("set_multicast_ttl_v4 call failed");
+    /// assert_eq!(socket.multicast_ttl_v4().unwrap(), 42);
+    /// ```
+    // #[stable(feature = "net2_mutators", since = "1.9.0")]
+    pub fn multicast_ttl_v4(&self) -> io::Result<u32> {
+        self.0.multicast_ttl_v4()
+    }
+
+    /// Sets the value of the `IPV6_MULTICAST_LOOP` option for this socket.
+    ///
+    /// Controls whether this socket sees the multicast packets it sends itself.
+    /// Note that this may not have any affect on IPv4 sockets.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::UdpSocket;
+    ///
+    /// let socket = UdpSocket::bind("[::1]:34254").expect("couldn't bind to address");
+    /// socket.set_multicast_loop_v6(false).expect("set_multicast_loop_v6 call failed");
+    /// ```
+    // #[stable(feature = "net2_mutators", since = "1.9.0")]
+    pub fn set_multicast_loop_v6(&self, multicast_loop_v6: bool) -> io::Result<()> {
+        self.0.set_multicast_loop_v6(multicast_loop_v6)
+    }
+
+    /// Gets the value of the `IPV6_MULTICAST_LOOP` option for this socket.
+    ///
+    /// For more information about this option, see
+    /// [`set_multicast_loop_v6`][link].
+    ///
+    /// [link]: #method.set_multicast_loop_v6
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::UdpSocket;
+    ///
+    /// let socket = UdpSocket::bind("[::1]:34254").expect("couldn't bind to address");
+    /// socket.set_multicast_loop_v6(false).expect("set_multicast_loop_v6 call failed");
+    /// assert_eq!(socket.multicast_loop_v6().unwrap(), false);
+    /// ```
+    // #[stable(feature = "net2_mutators", since = "1.9.0")]
+    pub fn multicast_loop_v6(&self) -> io::Result<bool> {
+        self.0.multicast_loop_v6()
+    }
+
+    /// Sets the value of the `IPV6_MULTICAST_HOPS` option for this socket.
+    ///
+    /// Indicates the hop limit of outgoing multicast packets for this socket,
+    /// the IPv6 counterpart of [`set_multicast_ttl_v4`]. The default value is
+    /// 1, which keeps multicast packets on the local network.
+    ///
+    /// [`set_multicast_ttl_v4`]: #method.set_multicast_ttl_v4
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::UdpSocket;
+    ///
+    /// let socket = UdpSocket::bind("[::1]:34254").expect("couldn't bind to address");
+    /// socket.set_multicast_hops_v6(8).expect("set_multicast_hops_v6 call failed");
+    /// ```
+    pub fn set_multicast_hops_v6(&self, multicast_hops_v6: u32) -> io::Result<()> {
+        self.0.set_multicast_hops_v6(multicast_hops_v6)
+    }
+
+    /// Gets the value of the `IPV6_MULTICAST_HOPS` option for this socket.
+    ///
+    /// For more information about this option, see
+    /// [`set_multicast_hops_v6`][link].
+    ///
+    /// [link]: #method.set_multicast_hops_v6
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::UdpSocket;
+    ///
+    /// let socket = UdpSocket::bind("[::1]:34254").expect("couldn't bind to address");
+    /// socket.set_multicast_hops_v6(8).expect("set_multicast_hops_v6 call failed");
+    /// assert_eq!(socket.multicast_hops_v6().unwrap(), 8);
+    /// ```
+    pub fn multicast_hops_v6(&self) -> io::Result<u32> {
+        self.0.multicast_hops_v6()
+    }
+
+    /// Sets the value of the `IP_MULTICAST_IF` option for this socket.
+    ///
+    /// `interface` is the address of the local interface that outgoing
+    /// multicast packets are sent through. If it's equal to `INADDR_ANY` then
+    /// the system chooses the interface from its routing table.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::{Ipv4Addr, UdpSocket};
+    ///
+    /// let socket = UdpSocket::bind("0.0.0.0:34254").expect("couldn't bind to address");
+    /// socket.set_multicast_if_v4(&Ipv4Addr::new(192, 168, 0, 10))
+    ///       .expect("set_multicast_if_v4 call failed");
+    /// ```
+    pub fn set_multicast_if_v4(&self, interface: &Ipv4Addr) -> io::Result<()> {
+        self.0.set_multicast_if_v4(interface)
+    }
+
+    /// Gets the value of the `IP_MULTICAST_IF` option for this socket.
+    ///
+    /// For more information about this option, see
+    /// [`set_multicast_if_v4`][link].
+    ///
+    /// [link]: #method.set_multicast_if_v4
+    pub fn multicast_if_v4(&self) -> io::Result<Ipv4Addr> {
+        self.0.multicast_if_v4()
+    }
+
+    /// Sets the value of the `IPV6_MULTICAST_IF` option for this socket.
+    ///
+    /// `interface` is the index of the local interface that outgoing
+    /// multicast packets are sent through, or 0 to let the system choose.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::UdpSocket;
+    ///
+    /// let socket = UdpSocket::bind("[::]:34254").expect("couldn't bind to address");
+    /// socket.set_multicast_if_v6(3).expect("set_multicast_if_v6 call failed");
+    /// ```
+    pub fn set_multicast_if_v6(&self, interface: u32) -> io::Result<()> {
+        self.0.set_multicast_if_v6(interface)
+    }
+
+    /// Gets the value of the `IPV6_MULTICAST_IF` option for this socket.
+    ///
+    /// For more information about this option, see
+    /// [`set_multicast_if_v6`][link].
+    ///
+    /// [link]: #method.set_multicast_if_v6
+    pub fn multicast_if_v6(&self) -> io::Result<u32> {
+        self.0.multicast_if_v6()
+    }
+
+    /// Sets the value for the `IP_TTL` option on this socket.
+    ///
+    /// This value sets the time-to-live field that is used in every packet sent
+    /// from this socket.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::UdpSocket;
+    ///
+    /// let socket = UdpSocket::bind("127.0.0.1:34254").expect("couldn't bind to address");
+    /// socket.set_ttl(42).expect("set_ttl call failed");
+    /// ```
+    // #[stable(feature = "net2_mutators", since = "1.9.0")]
+    pub fn set_ttl(&self, ttl: u32) -> io::Result<()> {
+        self.0.set_ttl(ttl)
+    }
+
+    /// Gets the value of the `IP_TTL` option for this socket.
+    ///
+    /// For more information about this option, see [`set_ttl`][link].
+    ///
+    /// [link]: #method.set_ttl
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::UdpSocket;
+    ///
+    /// let socket = UdpSocket::bind("127.0.0.1:34254").expect("couldn't bind to address");
+    /// socket.set_ttl(42).expect("set_ttl call failed");
+    /// assert_eq!(socket.ttl().unwrap(), 42);
+    /// ```
+    // #[stable(feature = "net2_mutators", since = "1.9.0")]
+    pub fn ttl(&self) -> io::Result<u32> {
+        self.0.ttl()
+    }
+
+    /// Sets the value of the `IP_TOS` option for this socket, which marks
+    /// every IPv4 datagram sent from it with the given codepoint.
+    ///
+    /// For more information about this option, see [`Dscp`].
+    ///
+    /// [`Dscp`]: struct.Dscp.html
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::{Dscp, UdpSocket};
+    ///
+    /// let socket = UdpSocket::bind("127.0.0.1:34254").expect("couldn't bind to address");
+    /// socket.set_tos_v4(Dscp::EF).expect("set_tos_v4 call failed");
+    /// ```
+    pub fn set_tos_v4(&self, dscp: Dscp) -> io::Result<()> {
+        self.0.set_tos_v4(dscp)
+    }
+
+    /// Gets the value of the `IP_TOS` option for this socket.
+    ///
+    /// For more information about this option, see [`set_tos_v4`][link].
+    ///
+    /// [link]: #method.set_tos_v4
+    pub fn tos_v4(&self) -> io::Result<Dscp> {
+        self.0.tos_v4()
+    }
+
+    /// Sets the value of the `IPV6_TCLASS` option for this socket, which marks
+    /// every IPv6 datagram sent from it with the given codepoint.
+    ///
+    /// For more information about this option, see [`Dscp`].
+    ///
+    /// [`Dscp`]: struct.Dscp.html
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::{Dscp, UdpSocket};
+    ///
+    /// let socket = UdpSocket::bind("[::1]:34254").expect("couldn't bind to address");
+    /// socket.set_tclass_v6(Dscp::EF).expect("set_tclass_v6 call failed");
+    /// ```
+    pub fn set_tclass_v6(&self, dscp: Dscp) -> io::Result<()> {
+        self.0.set_tclass_v6(dscp)
+    }
+
+    /// Gets the value of the `IPV6_TCLASS` option for this socket.
+    ///
+    /// For more information about this option, see [`set_tclass_v6`][link].
+    ///
+    /// [link]: #method.set_tclass_v6
+    pub fn tclass_v6(&self) -> io::Result<Dscp> {
+        self.0.tclass_v6()
+    }
+
+    /// Sets the value of the `IP_DONTFRAGMENT` option for this socket.
+    ///
+    /// If enabled, IPv4 datagrams sent from this socket have the
+    /// don't-fragment flag set. Sending a datagram larger than the path MTU
+    /// the system knows of then fails instead of fragmenting it, and a router
+    /// further along that drops one for being too large lowers [`path_mtu`].
+    ///
+    /// [`path_mtu`]: #method.path_mtu
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::UdpSocket;
+    ///
+    /// let socket = UdpSocket::bind("127.0.0.1:34254").expect("couldn't bind to address");
+    /// socket.set_dont_fragment(true).expect("set_dont_fragment call failed");
+    /// ```
+    pub fn set_dont_fragment(&self, dont_fragment: bool) -> io::Result<()> {
+        self.0.set_dont_fragment(dont_fragment)
+    }
+
+    /// Gets the value of the `IP_DONTFRAGMENT` option for this socket.
+    ///
+    /// For more information about this option, see [`set_dont_fragment`][link].
+    ///
+    /// [link]: #method.set_dont_fragment
+    pub fn dont_fragment(&self) -> io::Result<bool> {
+        self.0.dont_fragment()
+    }
+
+    /// Gets the value of the `IP_MTU` option for this socket.
+    ///
+    /// This is the path MTU the system currently knows of towards the address
+    /// this socket is [`connect`]ed to, and fails if it isn't connected. It is
+    /// the largest datagram, headers included, that can be sent unfragmented.
+    ///
+    /// [`connect`]: #method.connect
+    pub fn path_mtu(&self) -> io::Result<u32> {
+        self.0.path_mtu()
+    }
+
+    /// Sets the value of the `IPV6_DONTFRAG` option for this socket.
+    ///
+    /// This is the IPv6 counterpart of [`set_dont_fragment`]. With it
+    /// enabled, a datagram larger than the path MTU fails to send instead of
+    /// being fragmented by this host.
+    ///
+    /// [`set_dont_fragment`]: #method.set_dont_fragment
+    pub fn set_dont_fragment_v6(&self, dont_fragment: bool) -> io::Result<()> {
+        self.0.set_dont_fragment_v6(dont_fragment)
+    }
+
+    /// Gets the value of the `IPV6_DONTFRAG` option for this socket.
+    ///
+    /// For more information about this option, see
+    /// [`set_dont_fragment_v6`][link].
+    ///
+    /// [link]: #method.set_dont_fragment_v6
+    pub fn dont_fragment_v6(&self) -> io::Result<bool> {
+        self.0.dont_fragment_v6()
+    }
+
+    /// Gets the value of the `IPV6_MTU` option for this socket.
+    ///
+    /// This is the IPv6 counterpart of [`path_mtu`].
+    ///
+    /// [`path_mtu`]: #method.path_mtu
+    pub fn path_mtu_v6(&self) -> io::Result<u32> {
+        self.0.path_mtu_v6()
+    }
+
+    /// Executes an operation of the `IP_ADD_MEMBERSHIP` type.
+    ///
+    /// This function specifies a new multicast group for this socket to join.
+    /// The address must be a valid multicast address, and `interface` is the
+    /// address of the local interface with which the system should join the
+    /// multicast group. If it's equal to `INADDR_ANY` then an appropriate
+    /// interface is chosen by the system.
+    // #[stable(feature = "net2_mutators", since = "1.9.0")]
+    pub fn join_multicast_v4(&self, multiaddr: &Ipv4Addr, interface: &Ipv4Addr) -> io::Result<()> {
+        self.0.join_multicast_v4(multiaddr, interface)
+    }
+
+    /// Executes an operation of the `IPV6_ADD_MEMBERSHIP` type.
+    ///
+    /// This function specifies a new multicast group for this socket to join.
+    /// The address must be a valid multicast address, and `interface` is the
+    /// index of the interface to join/leave (or 0 to indicate any interface).
+    // #[stable(feature = "net2_mutators", since = "1.9.0")]
+    pub fn join_multicast_v6(&self, multiaddr: &Ipv6Addr, interface: u32) -> io::Result<()> {
+        self.0.join_multicast_v6(multiaddr, interface)
+    }
+
+    /// Executes an operation of the `IP_DROP_MEMBERSHIP` type.
+    ///
+    /// For more information about this option, see
+    /// [`join_multicast_v4`][link].
+    ///
+    /// [link]: #method.join_multicast_v4
+    // #[stable(feature = "net2_mutators", since = "1.9.0")]
+    pub fn leave_multicast_v4(&self, multiaddr: &Ipv4Addr, interface: &Ipv4Addr) -> io::Result<()> {
+        self.0.leave_multicast_v4(multiaddr, interface)
+    }
+
+    /// Executes an operation of the `IPV6_DROP_MEMBERSHIP` type.
+    ///
+    /// For more information about this option, see
+    /// [`join_multicast_v6`][link].
+    ///
+    /// [link]: #method.join_multicast_v6
+    // #[stable(feature = "net2_mutators", since = "1.9.0")]
+    pub fn leave_multicast_v6(&self, multiaddr: &Ipv6Addr, interface: u32) -> io::Result<()> {
+        self.0.leave_multicast_v6(multiaddr, interface)
+    }
+
+    /// Executes an operation of the `IP_ADD_SOURCE_MEMBERSHIP` type.
+    ///
+    /// This function joins a source-specific multicast group: only packets
+    /// sent to `multiaddr` by `source` are received. The address must be a
+    /// valid multicast address, and `interface` is the address of the local
+    /// interface with which the system should join the group. If it's equal
+    /// to `INADDR_ANY` then an appropriate interface is chosen by the system.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::{Ipv4Addr, UdpSocket};
+    ///
+    /// let socket = UdpSocket::bind("0.0.0.0:5004").expect("couldn't bind to address");
+    /// socket.join_ssm_v4(&Ipv4Addr::new(232, 1, 1, 1),
+    ///                    &Ipv4Addr::new(10, 0, 0, 1),
+    ///                    &Ipv4Addr::new(192, 168, 0, 10))
+    ///       .expect("join_ssm_v4 call failed");
+    /// ```
+    pub fn join_ssm_v4(&self, multiaddr: &Ipv4Addr, source: &Ipv4Addr, interface: &Ipv4Addr)
+                       -> io::Result<()> {
+        self.0.join_ssm_v4(multiaddr, source, interface)
+    }
+
+    /// Executes an operation of the `IP_DROP_SOURCE_MEMBERSHIP` type.
+    ///
+    /// For more information about this option, see
+    /// [`join_ssm_v4`][link].
+    ///
+    /// [link]: #method.join_ssm_v4
+    pub fn leave_ssm_v4(&self, multiaddr: &Ipv4Addr, source: &Ipv4Addr, interface: &Ipv4Addr)
+                        -> io::Result<()> {
+        self.0.leave_ssm_v4(multiaddr, source, interface)
+    }
+
+    /// Get the value of the `SO_ERROR` option on this socket.
+    ///
+    /// This will retrieve the stored error in the underlying socket, clearing
+    /// the field in the process. This can be useful for checking errors between
+    /// calls.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::UdpSocket;
+    ///
+    /// let socket = UdpSocket::bind("127.0.0.1:34254").expect("couldn't bind to address");
+    /// match socket.take_error() {
+    ///     Ok(Some(error)) => println!("UdpSocket error: {:?}", error),
+    ///     Ok(None) => println!("No error"),
+    ///     Err(error) => println!("UdpSocket.take_error failed: {:?}", error),
+    /// }
+    /// ```
+    // #[stable(feature = "net2_mutators", since = "1.9.0")]
+    pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+        self.0.take_error()
+    }
+
+    /// Connects this UDP socket to a remote address, allowing the `send` and
+    /// `recv` syscalls to be used to send data and also applies filters to only
+    /// receive data from the specified address.
+    ///
+    /// If `addr` yields multiple addresses, `connect` will be attempted with
+    /// each of the addresses until the underlying OS function returns no
+    /// error. Note that usually, a successful `connect` call does not specify
+    /// that there is a remote server listening on the port, rather, such an
+    /// error would only be detected after the first send. If the OS returns an
+    /// error for each of the specified addresses, the error returned from the
+    /// last connection attempt (the last address) is returned.
+    ///
+    /// # Examples
+    ///
+    /// Create a UDP socket bound to `127.0.0.1:3400` and connect the socket to
+    /// `127.0.0.1:8080`:
+    ///
+    /// ```ignore
+    /// use std::net::UdpSocket;
+    ///
+    /// let socket = UdpSocket::bind("127.0.0.1:3400").expect("couldn't bind to address");
+    /// socket.connect("127.0.0.1:8080").expect("connect function failed");
+    /// ```
+    // #[stable(feature = "net2_mutators", since = "1.9.0")]
+    pub fn connect<A: ToSocketAddrs>(&self, addr: A) -> io::Result<()> {
+        super::each_addr(addr, |addr| self.0.connect(addr))
+    }
+
+    /// Sends data on the socket to the remote address to which it is connected.
+    ///
+    /// The [`connect`] method will connect this socket to a remote address. This
+    /// method will fail if the socket is not connected.
+    ///
+    /// [`connect`]: #method.connect
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::UdpSocket;
+    ///
+    /// let socket = UdpSocket::bind("127.0.0.1:34254").expect("couldn't bind to address");
+    /// socket.connect("127.0.0.1:8080").expect("connect function failed");
+    /// socket.send(&[0, 1, 2]).expect("couldn't send message");
+    /// ```
+    // #[stable(feature = "net2_mutators", since = "1.9.0")]
+    pub fn send(&self, buf: &[u8]) -> io::Result<usize> {
+        self.0.send(buf)
+    }
+
+    /// Receives a single datagram message on the socket from the remote address to
+    /// which it is connected. On success, returns the number of bytes read.
+    ///
+    /// The function must be called with valid byte array `buf` of sufficient size to
+    /// hold the message bytes. If a message is too long to fit in the supplied buffer,
+    /// excess bytes may be discarded.
+    ///
+    /// The [`connect`] method will connect this socket to a remote address. This
+    /// method will fail if the socket is not connected.
+    ///
+    /// [`connect`]: #method.connect
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::UdpSocket;
+    ///
+    /// let socket = UdpSocket::bind("127.0.0.1:34254").expect("couldn't bind to address");
+    /// socket.connect("127.0.0.1:8080").expect("connect function failed");
+    /// let mut buf = [0; 10];
+    /// match socket.recv(&mut buf) {
+    ///     Ok(received) => println!("received {} bytes {:?}", received, &buf[..received]),
+    ///     Err(e) => println!("recv function failed: {:?}", e),
+    /// }
+    /// ```
+    // #[stable(feature = "net2_mutators", since = "1.9.0")]
+    pub fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.recv(buf)
+    }
+
+    /// Receives single datagram on the socket from the remote address to which it is
+    /// connected, without removing the message from input queue. On success, returns
+    /// the number of bytes peeked.
+    ///
+    /// The function must be called with valid byte array `buf` of sufficient size to
+    /// hold the message bytes. If a message is too long to fit in the supplied buffer,
+    /// excess bytes may be discarded.
+    ///
+    /// Successive calls return the same data. This is accomplished by passing
+    /// `MSG_PEEK` as a flag to the underlying `recv` system call.
+    ///
+    /// Do not use this function to implement busy waiting, instead use `libc::poll` to
+    /// synchronize IO events on one or more sockets.
+    ///
+    /// The [`connect`] method will connect this socket to a remote address. This
+    /// method will fail if the socket is not connected.
+    ///
+    /// [`connect`]: #method.connect
+    ///
+    /// # Errors
+    ///
+    /// This method will fail if the socket is not connected. The `connect` method
+    /// will connect this socket to a remote address.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::UdpSocket;
+    ///
+    /// let socket = UdpSocket::bind("127.0.0.1:34254").expect("couldn't bind to address");
+    /// socket.connect("127.0.0.1:8080").expect("connect function failed");
+    /// let mut buf = [0; 10];
+    /// match socket.peek(&mut buf) {
+    ///     Ok(received) => println!("received {} bytes", received),
+    ///     Err(e) => println!("peek function failed: {:?}", e),
+    /// }
+    /// ```
+    // #[stable(feature = "peek", since = "1.18.0")]
+    pub fn peek(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.peek(buf)
+    }
+
+    /// Moves this UDP socket into or out of nonblocking mode.
+    ///
+    /// This will result in `recv`, `recv_from`, `send`, and `send_to`
+    /// operations becoming nonblocking, i.e. immediately returning from their
+    /// calls. If the IO operation is successful, `Ok` is returned and no
+    /// further action is required. If the IO operation could not be completed
+    /// and needs to be retried, an error with kind
+    /// [`io::ErrorKind::WouldBlock`] is returned.
+    ///
+    /// On Windows calling `ioctlsocket` corresponds to calling
+    /// `ioctlsocket FIONBIO`.
+    ///
+    /// [`io::ErrorKind::WouldBlock`]: ../io/enum.ErrorKind.html#variant.WouldBlock
+    ///
+    /// # Examples
+    ///
+    /// Create a UDP socket bound to `127.0.0.1:7878` and read bytes in
+    /// nonblocking mode:
+    ///
+    /// ```ignore
+    /// use std::io;
+    /// use std::net::UdpSocket;
+    ///
+    /// let socket = UdpSocket::bind("127.0.0.1:7878").unwrap();
+    /// socket.set_nonblocking(true).unwrap();
+    ///
+    /// # fn wait_for_fd() { unimplemented!() }
+    /// let mut buf = [0; 10];
+    /// let (num_bytes_read, _) = loop {
+    ///     match socket.recv_from(&mut buf) {
+    ///         Ok(n) => break n,
+    ///         Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {
+    ///             // wait until network socket is ready, typically implemented
+    ///             // via platform-specific APIs such as epoll or IOCP
+    ///             wait_for_fd();
+    ///         }
+    ///         Err(e) => panic!("encountered IO error: {}", e),
+    ///     }
+    /// };
+    /// println!("bytes: {:?}", &buf[..num_bytes_read]);
+    /// ```
+    // #[stable(feature = "net2_mutators", since = "1.9.0")]
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        self.0.set_nonblocking(nonblocking)
+    }
+}
+
+impl AsInner<net_imp::UdpSocket> for UdpSocket {
+    fn as_inner(&self) -> &net_imp::UdpSocket { &self.0 }
+}
+
+impl FromInner<net_imp::UdpSocket> for UdpSocket {
+    fn from_inner(inner: net_imp::UdpSocket) -> UdpSocket { UdpSocket(inner) }
+}
+
+impl IntoInner<net_imp::UdpSocket> for UdpSocket {
+    fn into_inner(self) -> net_imp::UdpSocket { self.0 }
+}
+
+// #[stable(feature = "rust1", since = "1.0.0")]
+impl fmt::Debug for UdpSocket {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// A builder for a [`UdpSocket`], for socket options that only take effect if
+/// they are set before the socket is bound.
+///
+/// The options are the same as for [`TcpBuilder`], apart from the backlog.
+///
+/// [`UdpSocket`]: struct.UdpSocket.html
+/// [`TcpBuilder`]: struct.TcpBuilder.html
+///
+/// # Examples
+///
+/// ```ignore
+/// use std::net::UdpBuilder;
+///
+/// let socket = UdpBuilder::new()
+///     .exclusive_address_use(true)
+///     .recv_buffer_size(1 << 20)
+///     .bind("0.0.0.0:5353")
+///     .unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct UdpBuilder {
+    opts: net_imp::BindOpts,
+}
+
+impl UdpBuilder {
+    /// Creates a builder with the same settings as [`UdpSocket::bind`].
+    ///
+    /// [`UdpSocket::bind`]: struct.UdpSocket.html#method.bind
+    pub fn new() -> UdpBuilder {
+        UdpBuilder { opts: net_imp::BindOpts::default() }
+    }
+
+    /// Sets the `SO_REUSEADDR` option.
+    pub fn reuse_address(&mut self, reuse: bool) -> &mut UdpBuilder {
+        self.opts.reuse_address = reuse;
+        self
+    }
+
+    /// Sets the `SO_EXCLUSIVEADDRUSE` option.
+    pub fn exclusive_address_use(&mut self, exclusive: bool) -> &mut UdpBuilder {
+        self.opts.exclusive_address_use = exclusive;
+        self
+    }
+
+    /// Sets the `SO_REUSEPORT` option, which Windows doesn't have: [`bind`]
+    /// fails with an error of kind `InvalidInput` if this is set.
+    ///
+    /// [`bind`]: #method.bind
+    pub fn reuse_port(&mut self, reuse: bool) -> &mut UdpBuilder {
+        self.opts.reuse_port = reuse;
+        self
+    }
+
+    /// Sets the `IPV6_V6ONLY` option.
+    pub fn only_v6(&mut self, only_v6: bool) -> &mut UdpBuilder {
+        self.opts.only_v6 = Some(only_v6);
+        self
+    }
+
+    /// Sets the `SO_SNDBUF` option. The size must fit in an `i32`.
+    pub fn send_buffer_size(&mut self, size: usize) -> &mut UdpBuilder {
+        self.opts.send_buffer_size = Some(size);
+        self
+    }
+
+    /// Sets the `SO_RCVBUF` option. The size must fit in an `i32`.
+    pub fn recv_buffer_size(&mut self, size: usize) -> &mut UdpBuilder {
+        self.opts.recv_buffer_size = Some(size);
+        self
+    }
+
+    /// Creates a socket with the configured options and binds it to `addr`,
+    /// trying each address `addr` yields in turn as with [`UdpSocket::bind`].
+    ///
+    /// [`UdpSocket::bind`]: struct.UdpSocket.html#method.bind
+    pub fn bind<A: ToSocketAddrs>(&self, addr: A) -> io::Result<UdpSocket> {
+        super::each_addr(addr, |addr| net_imp::UdpSocket::bind_opts(addr, &self.opts))
+            .map(UdpSocket)
+    }
+}
+
+impl Default for UdpBuilder {
+    fn default() -> UdpBuilder {
+        UdpBuilder::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::ErrorKind;
+    use net::*;
+
+    use net::test::{next_test_ip4, next_test_ip6};
+    use sys_common::AsInner;
+    use std::time::{Instant, Duration, SystemTime};
+    use std::thread;
+    use std::sync::mpsc::channel;
+
+    fn each_ip(f: &mut dyn FnMut(SocketAddr, SocketAddr)) {
+        f(next_test_ip4(), next_test_ip4());
+        f(next_test_ip6(), next_test_ip6());
+    }
+
+    macro_rules! t {
+        ($e:expr) => {
+            match $e {
+                Ok(t) => t,
+                Err(e) => panic!("received error for `{}`: {}", stringify!($e), e),
+            }
+        }
+    }
+
+    #[test]
+    fn bind_error() {
+        match UdpSocket::bind("1.1.1.1:9999") {
+            Ok(..) => panic!(),
+            Err(e) => {
+                assert_eq!(e.kind(), ErrorKind::AddrNotAvailable)
+            }
+        }
+    }
+
+    #[test]
+    fn socket_smoke_test_ip4() {
+        each_ip(&mut |server_ip, client_ip| {
+            let (tx1, rx1) = channel();
+            let (tx2, rx2) = channel();
+
+            let _t = thread::spawn(move|| {
+                let client = t!(UdpSocket::bind(&client_ip));
+                rx1.recv().unwrap();
+                t!(client.send_to(&[99], &server_ip));
+                tx2.send(()).unwrap();
+            });
+
+            let server = t!(UdpSocket::bind(&server_ip));
+            tx1.send(()).unwrap();
+            let mut buf = [0];
+            let (nread, src) = t!(server.recv_from(&mut buf));
+            assert_eq!(nread, 1);
+            assert_eq!(buf[0], 99);
+            assert_eq!(src, client_ip);
+            rx2.recv().unwrap();
+        })
+    }
+
+    #[test]
+    fn socket_name_ip4() {
+        each_ip(&mut |addr, _| {
+            let server = t!(UdpSocket::bind(&addr));
+            assert_eq!(addr, t!(server.local_addr()));
+        })
+    }
+
+    #[test]
+    fn udp_clone_smoke() {
+        each_ip(&mut |addr1, addr2| {
+            let sock1 = t!(UdpSocket::bind(&addr1));
+            let sock2 = t!(UdpSocket::bind(&addr2));
+
+            let _t = thread::spawn(move|| {
+                let mut buf = [0, 0];
+                assert_eq!(sock2.recv_from(&mut buf).unwrap(), (1, addr1));
+                assert_eq!(buf[0], 1);
+                t!(sock2.send_to(&[2], &addr1));
+            });
+
+            let sock3 = t!(sock1.try_clone());
+
+            let (tx1, rx1) = channel();
+            let (tx2, rx2) = channel();
+            let _t = thread::spawn(move|| {
+                rx1.recv().unwrap();
+                t!(sock3.send_to(&[1], &addr2));
+                tx2.send(()).unwrap();
+            });
+            tx1.send(()).unwrap();
+            let mut buf = [0, 0];
+            assert_eq!(sock1.recv_from(&mut buf).unwrap(), (1, addr2));
+            rx2.recv().unwrap();
+        })
+    }
+
+    #[test]
+    fn udp_clone_two_read() {
+        each_ip(&mut |addr1, addr2| {
+            let sock1 = t!(UdpSocket::bind(&addr1));
+            let sock2 = t!(UdpSocket::bind(&addr2));
+            let (tx1, rx) = channel();
+            let tx2 = tx1.clone();
+
+            let _t = thread::spawn(move|| {
+                t!(sock2.send_to(&[1], &addr1));
+                rx.recv().unwrap();
+                t!(sock2.send_to(&[2], &addr1));
+                rx.recv().unwrap();
+            });
+
+            let sock3 = t!(sock1.try_clone());
+
+            let (done, rx) = channel();
+            let _t = thread::spawn(move|| {
+                let mut buf = [0, 0];
+                t!(sock3.recv_from(&mut buf));
+                tx2.send(()).unwrap();
+                done.send(()).unwrap();
+            });
+            let mut buf = [0, 0];
+            t!(sock1.recv_from(&mut buf));
+            tx1.send(()).unwrap();
+
+            rx.recv().unwrap();
+        })
+    }
+
+    #[test]
+    fn udp_clone_two_write() {
+        each_ip(&mut |addr1, addr2| {
+            let sock1 = t!(UdpSocket::bind(&addr1));
+            let sock2 = t!(UdpSocket::bind(&addr2));
+
+            let (tx, rx) = channel();
+            let (serv_tx, serv_rx) = channel();
+
+            let _t = thread::spawn(move|| {
+                let mut buf = [0, 1];
+                rx.recv().unwrap();
+                t!(sock2.recv_from(&mut buf));
+                serv_tx.send(()).unwrap();
+            });
+
+            let sock3 = t!(sock1.try_clone());
+
+            let (done, rx) = channel();
+            let tx2 = tx.clone();
+            let _t = thread::spawn(move|| {
+                match sock3.send_to(&[1], &addr2) {
+                    Ok(..) => { let _ = tx2.send(()); }
+                    Err(..) => {}
+                }
+                done.send(()).unwrap();
+            });
+            match sock1.send_to(&[2], &addr2) {
+                Ok(..) => { let _ = tx.send(()); }
+                Err(..) => {}
+            }
+            drop(tx);
+
+            rx.recv().unwrap();
+            serv_rx.recv().unwrap();
+        })
+    }
+
+    #[test]
+    fn debug() {
+        let name = "socket";
+        let socket_addr = next_test_ip4();
+
+        let udpsock = t!(UdpSocket::bind(&socket_addr));
+        let udpsock_inner = udpsock.0.socket().as_inner();
+        let compare = format!("UdpSocket {{ addr: {:?}, {}: {:?} }}",
+                              socket_addr, name, udpsock_inner);
+        assert_eq!(format!("{:?}", udpsock), compare);
+    }
+
+    #[test]
+    fn timeouts() {
+        let addr = next_test_ip4();
+
+        let stream = t!(UdpSocket::bind(&addr));
+        let dur = Duration::new(15410, 0);
+
+        assert_eq!(None, t!(stream.read_timeout()));
+
+        t!(stream.set_read_timeout(Some(dur)));
+        assert_eq!(Some(dur), t!(stream.read_timeout()));
+
+        assert_eq!(None, t!(stream.write_timeout()));
+
+        t!(stream.set_write_timeout(Some(dur)));
+        assert_eq!(Some(dur), t!(stream.write_timeout()));
+
+        t!(stream.set_read_timeout(None));
+        assert_eq!(None, t!(stream.read_timeout()));
+
+        t!(stream.set_write_timeout(None));
+        assert_eq!(None, t!(stream.write_timeout()));
+    }
+
+    #[test]
+    fn test_read_timeout() {
+        let addr = next_test_ip4();
+
+        let stream = t!(UdpSocket::bind(&addr));
+        t!(stream.set_read_timeout(Some(Duration::from_millis(1000))));
+
+        let mut buf = [0; 10];
+
+        let start = Instant::now();
+        let kind = stream.recv_from(&mut buf).err().expect("expected error").kind();
+        assert!(kind == ErrorKind::WouldBlock || kind == ErrorKind::TimedOut);
+        assert!(start.elapsed() > Duration::from_millis(400));
+    }
+
+    #[test]
+    fn test_read_with_timeout() {
+        let addr = next_test_ip4();
+
+        let stream = t!(UdpSocket::bind(&addr));
+        t!(stream.set_read_timeout(Some(Duration::from_millis(1000))));
+
+        t!(stream.send_to(b"hello world", &addr));
+
+        let mut buf = [0; 11];
+        t!(stream.recv_from(&mut buf));
+        assert_eq!(b"hello world", &buf[..]);
+
+        let start = Instant::now();
+        let kind = stream.recv_from(&mut buf).err().expect("expected error").kind();
+        assert!(kind == ErrorKind::WouldBlock || kind == ErrorKind::TimedOut);
+        assert!(start.elapsed() > Duration::from_millis(400));
+    }
+
+    // Ensure the `set_read_timeout` and `set_write_timeout` calls return errors
+    // when passed zero Durations
+    #[test]
+    fn test_timeout_zero_duration() {
+        let addr = next_test_ip4();
+
+        let socket = t!(UdpSocket::bind(&addr));
+
+        let result = socket.set_write_timeout(Some(Duration::new(0, 0)));
+        let err = result.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+        let result = socket.set_read_timeout(Some(Duration::new(0, 0)));
+        let err = result.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn connect_send_recv() {
+        let addr = next_test_ip4();
+
+        let socket = t!(UdpSocket::bind(&addr));
+        t!(socket.connect(addr));
+
+        t!(socket.send(b"hello world"));
+
+        let mut buf = [0; 11];
+        t!(socket.recv(&mut buf));
+        assert_eq!(b"hello world", &buf[..]);
+    }
+
+    #[test]
+    fn connect_send_peek_recv() {
+        each_ip(&mut |addr, _| {
+            let socket = t!(UdpSocket::bind(&addr));
+            t!(socket.connect(addr));
+
+            t!(socket.send(b"hello world"));
+
+            for _ in 1..3 {
+                let mut buf = [0; 11];
+                let size = t!(socket.peek(&mut buf));
+                assert_eq!(b"hello world", &buf[..]);
+                assert_eq!(size, 11);
+            }
+
+            let mut buf = [0; 11];
+            let size = t!(socket.recv(&mut buf));
+            assert_eq!(b"hello world", &buf[..]);
+            assert_eq!(size, 11);
+        })
+    }
+
+    #[test]
+    fn peek_from() {
+        each_ip(&mut |addr, _| {
+            let socket = t!(UdpSocket::bind(&addr));
+            t!(socket.send_to(b"hello world", &addr));
+
+            for _ in 1..3 {
+                let mut buf = [0; 11];
+                let (size, _) = t!(socket.peek_from(&mut buf));
+                assert_eq!(b"hello world", &buf[..]);
+                assert_eq!(size, 11);
+            }
+
+            let mut buf = [0; 11];
+            let (size, _) = t!(socket.recv_from(&mut buf));
+            assert_eq!(b"hello world", &buf[..]);
+            assert_eq!(size, 11);
+        })
+    }
+
+    #[test]
+    fn send_recv_batch() {
+        each_ip(&mut |addr, _| {
+            let socket = t!(UdpSocket::bind(&addr));
+            let sent = t!(socket.send_batch(&[b"a", b"", b"ccc"], &[addr, addr, addr]));
+            assert_eq!(sent, 3);
+
+            let mut received = Vec::new();
+            while received.len() < 3 {
+                let mut bufs = [[0; 4]; 4];
+                let msgs = {
+                    let mut slices: Vec<&mut [u8]> = bufs.iter_mut().map(|b| &mut b[..]).collect();
+                    t!(socket.recv_batch(&mut slices))
+                };
+                assert!(!msgs.is_empty());
+                for (&(size, src), buf) in msgs.iter().zip(&bufs) {
+                    assert_eq!(src, addr);
+                    received.push(buf[..size].to_vec());
+                }
+            }
+            assert_eq!(received, [&b"a"[..], &b""[..], &b"ccc"[..]]);
+
+            // A datagram that doesn't fit its buffer ends the batch, and is
+            // left queued for the next receive.
+            t!(socket.send_batch(&[b"a", b"toolong"], &[addr, addr]));
+            let mut bufs = [[0; 4]; 2];
+            let msgs = {
+                let mut slices: Vec<&mut [u8]> = bufs.iter_mut().map(|b| &mut b[..]).collect();
+                t!(socket.recv_batch(&mut slices))
+            };
+            assert_eq!(msgs, [(1, addr)]);
+            let mut buf = [0; 7];
+            assert_eq!(t!(socket.recv_from(&mut buf)), (7, addr));
+            assert_eq!(&buf, b"toolong");
+
+            t!(socket.set_nonblocking(true));
+            let mut buf = [0; 4];
+            match socket.recv_batch(&mut [&mut buf[..]]) {
+                Err(ref e) if e.kind() == ErrorKind::WouldBlock => {}
+                Err(e) => panic!("unexpected error {}", e),
+                Ok(..) => panic!("unexpected success"),
+            }
+
+            match socket.send_batch(&[b"a"], &[]) {
+                Ok(..) => panic!("expected error"),
+                Err(ref e) if e.kind() == ErrorKind::InvalidInput => {}
+                Err(e) => panic!("unexpected error {}", e),
+            }
+        })
+    }
+
+    #[test]
+    fn recv_msg_packet_info() {
+        each_ip(&mut |addr, _| {
+            let socket = t!(UdpSocket::bind(&addr));
+            assert!(!t!(socket.recv_packet_info()));
+            t!(socket.set_recv_packet_info(true));
+            assert!(t!(socket.recv_packet_info()));
+
+            t!(socket.send_to(b"hello", &addr));
+            let mut buf = [0; 5];
+            let (size, src, info) = t!(socket.recv_msg(&mut buf));
+            assert_eq!(b"hello", &buf[..]);
+            assert_eq!(size, 5);
+            assert_eq!(src, addr);
+            let info = info.expect("no packet info");
+            assert_eq!(info.local_addr, addr.ip());
+
+            t!(socket.send_msg(b"world", &addr, &info));
+            let (size, _, _) = t!(socket.recv_msg(&mut buf));
+            assert_eq!(b"world", &buf[..]);
+            assert_eq!(size, 5);
+
+            t!(socket.send_to(b"hello world", &addr));
+            match socket.recv_msg(&mut buf) {
+                Err(ref e) if e.raw_os_error() == Some(10040) => {} // WSAEMSGSIZE
+                Err(e) => panic!("unexpected error {}", e),
+                Ok(_) => panic!("unexpected success"),
+            }
+        })
+    }
+
+    #[test]
+    fn ttl() {
+        let ttl = 100;
+
+        let addr = next_test_ip4();
+
+        let stream = t!(UdpSocket::bind(&addr));
+
+        t!(stream.set_ttl(ttl));
+        assert_eq!(ttl, t!(stream.ttl()));
+    }
+
+    #[test]
+    fn multicast_if_and_hops() {
+        let socket = t!(UdpSocket::bind(&next_test_ip4()));
+        t!(socket.set_multicast_if_v4(&Ipv4Addr::new(127, 0, 0, 1)));
+        assert_eq!(t!(socket.multicast_if_v4()), Ipv4Addr::new(127, 0, 0, 1));
+
+        let socket = t!(UdpSocket::bind(&next_test_ip6()));
+        t!(socket.set_multicast_if_v6(0));
+        assert_eq!(t!(socket.multicast_if_v6()), 0);
+        t!(socket.set_multicast_hops_v6(8));
+        assert_eq!(t!(socket.multicast_hops_v6()), 8);
+    }
+
+    #[test]
+    fn join_leave_ssm_v4() {
+        let group = Ipv4Addr::new(232, 1, 1, 1);
+        let source = Ipv4Addr::new(127, 0, 0, 1);
+        let socket = t!(UdpSocket::bind(&next_test_ip4()));
+        t!(socket.join_ssm_v4(&group, &source, &Ipv4Addr::new(127, 0, 0, 1)));
+        t!(socket.leave_ssm_v4(&group, &source, &Ipv4Addr::new(127, 0, 0, 1)));
+    }
+
+    #[test]
+    fn tos_and_tclass() {
+        assert_eq!(Dscp::new(46), Some(Dscp::EF));
+        assert_eq!(Dscp::new(64), None);
+        assert_eq!(Dscp::EF.to_tos(), 0xb8);
+        assert_eq!(Dscp::from_tos(0xb9), Dscp::EF);
+
+        // Whether the value reads back depends on the host's
+        // `DisableUserTOSSetting`, so only the calls themselves are checked.
+        let socket = t!(UdpSocket::bind(&next_test_ip4()));
+        t!(socket.set_tos_v4(Dscp::EF));
+        t!(socket.tos_v4());
+
+        let socket = t!(UdpSocket::bind(&next_test_ip6()));
+        t!(socket.set_tclass_v6(Dscp::EF));
+        t!(socket.tclass_v6());
+    }
+
+    #[test]
+    fn recv_event_unreachable() {
+        each_ip(&mut |addr1, addr2| {
+            let socket = t!(UdpSocket::bind(&addr1));
+            t!(socket.set_read_timeout(Some(Duration::from_millis(1000))));
+
+            // Nothing is bound to `addr2`.
+            t!(socket.send_to(b"hello", &addr2));
+            match t!(socket.recv_event(&mut [0; 5])) {
+                UdpEvent::Unreachable(Some(peer)) => assert_eq!(peer, addr2),
+                e => panic!("unexpected event {:?}", e),
+            }
+
+            t!(socket.set_connreset(false));
+            t!(socket.send_to(b"hello", &addr2));
+            t!(socket.send_to(b"world", &addr1));
+            let mut buf = [0; 5];
+            let (size, src) = t!(socket.recv_from(&mut buf));
+            assert_eq!(b"world", &buf[..]);
+            assert_eq!(size, 5);
+            assert_eq!(src, addr1);
+        })
+    }
+
+    #[test]
+    fn dont_fragment_and_path_mtu() {
+        let addr1 = next_test_ip4();
+        let addr2 = next_test_ip4();
+        let socket = t!(UdpSocket::bind(&addr1));
+        t!(socket.set_dont_fragment(true));
+        assert!(t!(socket.dont_fragment()));
+        t!(socket.connect(&addr2));
+        assert!(t!(socket.path_mtu()) > 0);
+
+        let addr1 = next_test_ip6();
+        let addr2 = next_test_ip6();
+        let socket = t!(UdpSocket::bind(&addr1));
+        t!(socket.set_dont_fragment_v6(true));
+        assert!(t!(socket.dont_fragment_v6()));
+        t!(socket.connect(&addr2));
+        assert!(t!(socket.path_mtu_v6()) > 0);
+    }
+
+    #[test]
+    fn recv_from_with_timestamp() {
+        each_ip(&mut |addr, _| {
+            let socket = t!(UdpSocket::bind(&addr));
+
+            t!(socket.send_to(b"hello", &addr));
+            let mut buf = [0; 5];
+            let (size, src, time) = t!(socket.recv_from_with_timestamp(&mut buf));
+            assert_eq!(size, 5);
+            assert_eq!(src, addr);
+            assert_eq!(time, None);
+
+            // Windows only supports receive timestamps from Windows 10
+            // version 2004 on.
+            match socket.set_recv_timestamps(true) {
+                Err(ref e) if e.raw_os_error() == Some(10022) || // WSAEINVAL
+                              e.raw_os_error() == Some(10045) => return, // WSAEOPNOTSUPP
+                r => t!(r),
+            }
+            let before = SystemTime::now() - Duration::from_secs(1);
+            t!(socket.send_to(b"hello", &addr));
+            let (size, _, time) = t!(socket.recv_from_with_timestamp(&mut buf));
+            assert_eq!(size, 5);
+            let time = time.expect("no timestamp");
+            assert!(time >= before);
+            assert!(time <= SystemTime::now());
+        })
+    }
+
+    #[test]
+    fn builder_reuse_address() {
+        each_ip(&mut |addr, _| {
+            let sock1 = t!(UdpBuilder::new().reuse_address(true).bind(&addr));
+            let sock2 = t!(UdpBuilder::new().reuse_address(true).bind(&addr));
+            assert_eq!(t!(sock1.local_addr()), t!(sock2.local_addr()));
+
+            assert!(UdpBuilder::new().exclusive_address_use(true).bind(&addr).is_err());
+        })
+    }
+
+    #[test]
+    fn builder_reuse_port() {
+        match UdpBuilder::new().reuse_port(true).bind(&next_test_ip4()) {
+            Err(ref e) if e.kind() == ErrorKind::InvalidInput => {}
+            Err(e) => panic!("unexpected error {}", e),
+            Ok(_) => panic!("unexpected success"),
+        }
+    }
+
+    #[test]
+    fn set_nonblocking() {
+        each_ip(&mut |addr, _| {
+            let socket = t!(UdpSocket::bind(&addr));
+
+            t!(socket.set_nonblocking(true));
+            t!(socket.set_nonblocking(false));
+
+            t!(socket.connect(addr));
+
+            t!(socket.set_nonblocking(false));
+            t!(socket.set_nonblocking(true));
+
+            let mut buf = [0];
+            match socket.recv(&mut buf) {
+                Ok(_) => panic!("expected error"),
+                Err(ref e) if e.kind() == ErrorKind::WouldBlock => {}
+                Err(e) => panic!("unexpected error {}", e),
+            }
+        })
+    }
+}
@@ -43,6 +43,7 @@ pub type WORD = u16;
 pub type CHAR = c_char;
 pub type ULONG_PTR = usize;
 pub type ULONG = c_ulong;
+pub type ULONG64 = u64;
 #[cfg(target_arch = "x86_64")]
 pub type ULONGLONG = u64;
 #[cfg(target_arch = "x86_64")]
@@ -65,9 +66,19 @@ pub type LPWCH = *mut WCHAR;
 pub type LPWIN32_FIND_DATAW = *mut WIN32_FIND_DATAW;
 pub type LPWSADATA = *mut WSADATA;
 pub type LPWSAPROTOCOL_INFO = *mut WSAPROTOCOL_INFO;
+pub type LPWSABUF = *mut WSABUF;
+pub type LPWSAOVERLAPPED = *mut OVERLAPPED;
+pub type LPWSAOVERLAPPED_COMPLETION_ROUTINE = ::std::option::Option<unsafe extern "system" fn(
+    dwError: DWORD,
+    cbTransferred: DWORD,
+    lpOverlapped: LPWSAOVERLAPPED,
+    dwFlags: DWORD,
+)>;
 pub type LPSTR = *mut CHAR;
 pub type LPWSTR = *mut WCHAR;
 pub type LPFILETIME = *mut FILETIME;
+pub type PSECURITY_DESCRIPTOR = LPVOID;
+pub type SECURITY_INFORMATION = DWORD;
 
 pub type PCONDITION_VARIABLE = *mut CONDITION_VARIABLE;
 pub type PLARGE_INTEGER = *mut c_longlong;
@@ -99,6 +110,8 @@ pub const FILE_APPEND_DATA: DWORD = 0x00000004;
 pub const FILE_WRITE_EA: DWORD = 0x00000010;
 pub const FILE_WRITE_ATTRIBUTES: DWORD = 0x00000100;
 pub const READ_CONTROL: DWORD = 0x00020000;
+pub const DELETE: DWORD = 0x00010000;
+pub const FILE_READ_ATTRIBUTES: DWORD = 0x00000080;
 pub const SYNCHRONIZE: DWORD = 0x00100000;
 pub const GENERIC_READ: DWORD = 0x80000000;
 pub const GENERIC_WRITE: DWORD = 0x40000000;
@@ -113,6 +126,10 @@ pub const FILE_FLAG_OPEN_REPARSE_POINT: DWORD = 0x00200000;
 pub const FILE_FLAG_BACKUP_SEMANTICS: DWORD = 0x02000000;
 pub const SECURITY_SQOS_PRESENT: DWORD = 0x00100000;
 
+pub const WRITE_DAC: DWORD = 0x00040000;
+pub const DACL_SECURITY_INFORMATION: SECURITY_INFORMATION = 0x00000004;
+pub const SDDL_REVISION_1: DWORD = 1;
+
 pub const FIONBIO: c_ulong = 0x8004667e;
 
 #[repr(C)]
@@ -139,6 +156,7 @@ pub const WSADESCRIPTION_LEN: usize = 256;
 pub const WSASYS_STATUS_LEN: usize = 128;
 pub const WSAPROTOCOL_LEN: DWORD = 255;
 pub const INVALID_SOCKET: SOCKET = !0;
+pub const SOCKET_ERROR: c_int = -1;
 
 pub const WSAEACCES: c_int = 10013;
 pub const WSAEINVAL: c_int = 10022;
@@ -146,6 +164,7 @@ pub const WSAEWOULDBLOCK: c_int = 10035;
 pub const WSAEADDRINUSE: c_int = 10048;
 pub const WSAEADDRNOTAVAIL: c_int = 10049;
 pub const WSAECONNABORTED: c_int = 10053;
+pub const WSAEMSGSIZE: c_int = 10040;
 pub const WSAECONNRESET: c_int = 10054;
 pub const WSAENOTCONN: c_int = 10057;
 pub const WSAESHUTDOWN: c_int = 10058;
@@ -159,6 +178,7 @@ pub const MAXIMUM_REPARSE_DATA_BUFFER_SIZE: usize = 16 * 1024;
 pub const FSCTL_GET_REPARSE_POINT: DWORD = 0x900a8;
 pub const IO_REPARSE_TAG_SYMLINK: DWORD = 0xa000000c;
 pub const IO_REPARSE_TAG_MOUNT_POINT: DWORD = 0xa0000003;
+pub const IO_REPARSE_TAG_AF_UNIX: DWORD = 0x80000023;
 pub const SYMLINK_FLAG_RELATIVE: DWORD = 0x00000001;
 pub const FSCTL_SET_REPARSE_POINT: DWORD = 0x900a4;
 
@@ -232,11 +252,25 @@ pub const SOL_SOCKET: c_int = 0xffff;
 pub const SO_RCVTIMEO: c_int = 0x1006;
 pub const SO_SNDTIMEO: c_int = 0x1005;
 pub const SO_REUSEADDR: c_int = 0x0004;
+pub const SO_EXCLUSIVEADDRUSE: c_int = !SO_REUSEADDR;
+pub const SO_SNDBUF: c_int = 0x1001;
+pub const SO_RCVBUF: c_int = 0x1002;
+pub const SO_KEEPALIVE: c_int = 0x0008;
+pub const SO_LINGER: c_int = 0x0080;
 pub const IPPROTO_IP: c_int = 0;
 pub const IPPROTO_TCP: c_int = 6;
 pub const IPPROTO_IPV6: c_int = 41;
 pub const TCP_NODELAY: c_int = 0x0001;
+pub const TCP_KEEPALIVE: c_int = 3;
+pub const TCP_KEEPCNT: c_int = 16;
+pub const TCP_KEEPINTVL: c_int = 17;
 pub const IP_TTL: c_int = 4;
+pub const IP_TOS: c_int = 3;
+pub const IPV6_TCLASS: c_int = 39;
+pub const IP_DONTFRAGMENT: c_int = 14;
+pub const IPV6_DONTFRAG: c_int = 14;
+pub const IP_MTU: c_int = 73;
+pub const IPV6_MTU: c_int = 72;
 pub const IPV6_V6ONLY: c_int = 27;
 pub const SO_ERROR: c_int = 0x1007;
 pub const SO_BROADCAST: c_int = 0x0020;
@@ -247,7 +281,80 @@ pub const IP_ADD_MEMBERSHIP: c_int = 12;
 pub const IP_DROP_MEMBERSHIP: c_int = 13;
 pub const IPV6_ADD_MEMBERSHIP: c_int = 12;
 pub const IPV6_DROP_MEMBERSHIP: c_int = 13;
+pub const IP_MULTICAST_IF: c_int = 9;
+pub const IPV6_MULTICAST_IF: c_int = 9;
+pub const IPV6_MULTICAST_HOPS: c_int = 10;
+pub const IP_ADD_SOURCE_MEMBERSHIP: c_int = 15;
+pub const IP_DROP_SOURCE_MEMBERSHIP: c_int = 16;
 pub const MSG_PEEK: c_int = 0x2;
+pub const MSG_TRUNC: c_int = 0x0100;
+pub const MSG_CTRUNC: c_int = 0x0200;
+pub const IP_PKTINFO: c_int = 19;
+pub const IPV6_PKTINFO: c_int = 19;
+
+pub const IOC_OUT: DWORD = 0x40000000;
+pub const IOC_IN: DWORD = 0x80000000;
+pub const IOC_VENDOR: DWORD = 0x18000000;
+pub const IOC_WS2: DWORD = 0x08000000;
+pub const SIO_GET_EXTENSION_FUNCTION_POINTER: DWORD = IOC_IN | IOC_OUT | IOC_WS2 | 6;
+pub const SIO_AF_UNIX_GETPEERPID: DWORD = IOC_OUT | IOC_VENDOR | 256;
+pub const SIO_UDP_CONNRESET: DWORD = IOC_IN | IOC_VENDOR | 12;
+pub const SIO_TIMESTAMPING: DWORD = IOC_IN | IOC_VENDOR | 235;
+pub const SIO_TCP_INFO: DWORD = IOC_IN | IOC_OUT | IOC_VENDOR | 39;
+
+pub const SO_TIMESTAMP: c_int = 0x300A;
+pub const TIMESTAMPING_FLAG_RX: ULONG = 0x1;
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct TCP_INFO_v0 {
+    pub State: c_int,
+    pub Mss: ULONG,
+    pub ConnectionTimeMs: ULONG64,
+    pub TimestampsEnabled: BOOLEAN,
+    pub RttUs: ULONG,
+    pub MinRttUs: ULONG,
+    pub BytesInFlight: ULONG,
+    pub Cwnd: ULONG,
+    pub SndWnd: ULONG,
+    pub RcvWnd: ULONG,
+    pub RcvBuf: ULONG,
+    pub BytesOut: ULONG64,
+    pub BytesIn: ULONG64,
+    pub BytesReordered: ULONG,
+    pub BytesRetrans: ULONG,
+    pub FastRetrans: ULONG,
+    pub DupAcksIn: ULONG,
+    pub TimeoutEpisodes: ULONG,
+    pub SynRetrans: BYTE,
+}
+
+#[repr(C)]
+pub struct TIMESTAMPING_CONFIG {
+    pub Flags: ULONG,
+    pub TxTimestampsBuffered: USHORT,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct linger {
+    pub l_onoff: USHORT,
+    pub l_linger: USHORT,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct IN_PKTINFO {
+    pub ipi_addr: in_addr,
+    pub ipi_ifindex: ULONG,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct IN6_PKTINFO {
+    pub ipi6_addr: in6_addr,
+    pub ipi6_ifindex: ULONG,
+}
 
 #[repr(C)]
 pub struct ip_mreq {
@@ -255,6 +362,13 @@ pub struct ip_mreq {
     pub imr_interface: in_addr,
 }
 
+#[repr(C)]
+pub struct ip_mreq_source {
+    pub imr_multiaddr: in_addr,
+    pub imr_sourceaddr: in_addr,
+    pub imr_interface: in_addr,
+}
+
 #[repr(C)]
 pub struct ipv6_mreq {
     pub ipv6mr_multiaddr: in6_addr,
@@ -344,6 +458,45 @@ pub struct WSAPROTOCOL_INFO {
     pub szProtocol: [u16; (WSAPROTOCOL_LEN as usize) + 1],
 }
 
+#[repr(C)]
+pub struct WSABUF {
+    pub len: ULONG,
+    pub buf: *mut CHAR,
+}
+
+#[repr(C)]
+pub struct WSAMSG {
+    pub name: *mut SOCKADDR,
+    pub namelen: c_int,
+    pub lpBuffers: LPWSABUF,
+    pub dwBufferCount: ULONG,
+    pub Control: WSABUF,
+    pub dwFlags: ULONG,
+}
+pub type LPWSAMSG = *mut WSAMSG;
+
+#[repr(C)]
+pub struct CMSGHDR {
+    pub cmsg_len: SIZE_T,
+    pub cmsg_level: c_int,
+    pub cmsg_type: c_int,
+}
+
+pub type LPFN_WSARECVMSG = unsafe extern "system" fn(s: SOCKET,
+                                                     lpMsg: LPWSAMSG,
+                                                     lpdwNumberOfBytesRecvd: LPDWORD,
+                                                     lpOverlapped: LPWSAOVERLAPPED,
+                                                     lpCompletionRoutine:
+                                                         LPWSAOVERLAPPED_COMPLETION_ROUTINE)
+                                                     -> c_int;
+
+pub const WSAID_WSARECVMSG: GUID = GUID {
+    Data1: 0xf689d7c8,
+    Data2: 0x6f1f,
+    Data3: 0x436b,
+    Data4: [0x8a, 0x53, 0xe5, 0x4f, 0xe3, 0x51, 0xc3, 0x22],
+};
+
 #[repr(C)]
 #[derive(Copy, Clone)]
 pub struct WIN32_FILE_ATTRIBUTE_DATA {
@@ -405,6 +558,17 @@ pub struct FILE_BASIC_INFO {
     pub FileAttributes: DWORD,
 }
 
+#[repr(C)]
+pub struct FILE_ATTRIBUTE_TAG_INFO {
+    pub FileAttributes: DWORD,
+    pub ReparseTag: DWORD,
+}
+
+#[repr(C)]
+pub struct FILE_DISPOSITION_INFO {
+    pub DeleteFile: BOOLEAN,
+}
+
 #[repr(C)]
 pub struct FILE_END_OF_FILE_INFO {
     pub EndOfFile: LARGE_INTEGER,
@@ -900,6 +1064,59 @@ extern "system" {
                       lpProtocolInfo: LPWSAPROTOCOL_INFO,
                       g: GROUP,
                       dwFlags: DWORD) -> SOCKET;
+    pub fn WSARecv(s: SOCKET,
+                   lpBuffers: LPWSABUF,
+                   dwBufferCount: DWORD,
+                   lpNumberOfBytesRecvd: LPDWORD,
+                   lpFlags: LPDWORD,
+                   lpOverlapped: LPWSAOVERLAPPED,
+                   lpCompletionRoutine: LPWSAOVERLAPPED_COMPLETION_ROUTINE)
+                   -> c_int;
+    pub fn WSARecvFrom(s: SOCKET,
+                       lpBuffers: LPWSABUF,
+                       dwBufferCount: DWORD,
+                       lpNumberOfBytesRecvd: LPDWORD,
+                       lpFlags: LPDWORD,
+                       lpFrom: *mut SOCKADDR,
+                       lpFromlen: *mut c_int,
+                       lpOverlapped: LPWSAOVERLAPPED,
+                       lpCompletionRoutine: LPWSAOVERLAPPED_COMPLETION_ROUTINE)
+                       -> c_int;
+    pub fn WSAIoctl(s: SOCKET,
+                    dwIoControlCode: DWORD,
+                    lpvInBuffer: LPVOID,
+                    cbInBuffer: DWORD,
+                    lpvOutBuffer: LPVOID,
+                    cbOutBuffer: DWORD,
+                    lpcbBytesReturned: LPDWORD,
+                    lpOverlapped: LPWSAOVERLAPPED,
+                    lpCompletionRoutine: LPWSAOVERLAPPED_COMPLETION_ROUTINE)
+                    -> c_int;
+    pub fn WSASend(s: SOCKET,
+                   lpBuffers: LPWSABUF,
+                   dwBufferCount: DWORD,
+                   lpNumberOfBytesSent: LPDWORD,
+                   dwFlags: DWORD,
+                   lpOverlapped: LPWSAOVERLAPPED,
+                   lpCompletionRoutine: LPWSAOVERLAPPED_COMPLETION_ROUTINE)
+                   -> c_int;
+    pub fn WSASendTo(s: SOCKET,
+                     lpBuffers: LPWSABUF,
+                     dwBufferCount: DWORD,
+                     lpNumberOfBytesSent: LPDWORD,
+                     dwFlags: DWORD,
+                     lpTo: *const SOCKADDR,
+                     iTolen: c_int,
+                     lpOverlapped: LPWSAOVERLAPPED,
+                     lpCompletionRoutine: LPWSAOVERLAPPED_COMPLETION_ROUTINE)
+                     -> c_int;
+    pub fn WSASendMsg(Handle: SOCKET,
+                      lpMsg: LPWSAMSG,
+                      dwFlags: DWORD,
+                      lpNumberOfBytesSent: LPDWORD,
+                      lpOverlapped: LPWSAOVERLAPPED,
+                      lpCompletionRoutine: LPWSAOVERLAPPED_COMPLETION_ROUTINE)
+                      -> c_int;
     pub fn ioctlsocket(s: SOCKET, cmd: c_long, argp: *mut c_ulong) -> c_int;
     pub fn InitializeCriticalSection(CriticalSection: *mut CRITICAL_SECTION);
     pub fn EnterCriticalSection(CriticalSection: *mut CRITICAL_SECTION);
@@ -938,6 +1155,14 @@ extern "system" {
     pub fn OpenProcessToken(ProcessHandle: HANDLE,
                             DesiredAccess: DWORD,
                             TokenHandle: *mut HANDLE) -> BOOL;
+    pub fn ConvertStringSecurityDescriptorToSecurityDescriptorW(
+        StringSecurityDescriptor: LPCWSTR,
+        StringSDRevision: DWORD,
+        SecurityDescriptor: *mut PSECURITY_DESCRIPTOR,
+        SecurityDescriptorSize: *mut ULONG) -> BOOL;
+    pub fn SetKernelObjectSecurity(Handle: HANDLE,
+                                   SecurityInformation: SECURITY_INFORMATION,
+                                   SecurityDescriptor: PSECURITY_DESCRIPTOR) -> BOOL;
     pub fn GetCurrentProcess() -> HANDLE;
     pub fn GetCurrentThread() -> HANDLE;
     pub fn GetStdHandle(which: DWORD) -> HANDLE;
@@ -1199,6 +1424,12 @@ compat_fn! {
                                 lpThreadDescription: LPCWSTR) -> HRESULT {
         SetLastError(ERROR_CALL_NOT_IMPLEMENTED as DWORD); E_NOTIMPL
     }
+    pub fn GetFileInformationByHandleEx(_hFile: HANDLE,
+                    _FileInformationClass: FILE_INFO_BY_HANDLE_CLASS,
+                    _lpFileInformation: LPVOID,
+                    _dwBufferSize: DWORD) -> BOOL {
+        SetLastError(ERROR_CALL_NOT_IMPLEMENTED as DWORD); 0
+    }
     pub fn SetFileInformationByHandle(_hFile: HANDLE,
                     _FileInformationClass: FILE_INFO_BY_HANDLE_CLASS,
                     _lpFileInformation: LPVOID,
@@ -1237,6 +1468,9 @@ compat_fn! {
     pub fn TryAcquireSRWLockShared(SRWLock: PSRWLOCK) -> BOOLEAN {
         panic!("rwlocks not available")
     }
+    pub fn GetSystemTimePreciseAsFileTime(lpSystemTimeAsFileTime: LPFILETIME) -> () {
+        GetSystemTimeAsFileTime(lpSystemTimeAsFileTime)
+    }
 }
 
 #[cfg(all(target_env = "gnu", feature = "backtrace"))]
@@ -44,21 +44,30 @@ pub mod netc {
 
 use std::ascii;
 use std::cmp;
+use std::env;
 // use std::ffi::OsStr;
 use std::fmt;
-use std::io::{self, Initializer};
+use std::hash::{Hash, Hasher};
+use std::fs;
+use std::io::{self, Initializer, Read, Write};
 use std::mem;
 // use std::net::{self, Shutdown};
-use net::Shutdown;
+use net::{self, Shutdown};
 // use std::os::windows::ffi::OsStrExt;
 // use os::unix::io::{RawFd, AsRawFd, FromRawFd, IntoRawFd};
-use std::path::Path;
-use std::time::Duration;
+use std::os::windows::io::{RawSocket, AsRawSocket, FromRawSocket, IntoRawSocket};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::ptr;
+use std::slice;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
 // use sys::{self, cvt};
 use sys::net::{cvt, init, wrlen_t};
 use sys::net::Socket;
 // use sys_common::{self, AsInner, FromInner, IntoInner};
-use sys_common::AsInner;
+use sys_common::{self, AsInner, FromInner, IntoInner};
 
 #[cfg(any(target_os = "linux", target_os = "android",
           target_os = "dragonfly", target_os = "freebsd",
@@ -170,6 +179,53 @@ impl SocketAddr {
         })
     }
 
+    fn unnamed() -> SocketAddr {
+        SocketAddr {
+            addr: unsafe { mem::zeroed() },
+            len: sun_path_offset() as libc::c_int,
+        }
+    }
+
+    // The part of `sun_path` covered by `len`.
+    fn sun_path(&self) -> &[u8] {
+        let len = self.len as usize - sun_path_offset();
+        let path = unsafe { mem::transmute::<&[libc::c_char], &[u8]>(&self.addr.sun_path) };
+        &path[..len]
+    }
+
+    // Whether both addresses name the same socket, no matter if the null
+    // terminator of a pathname was counted in `len`.
+    fn names_same(&self, other: &SocketAddr) -> bool {
+        match (self.address(), other.address()) {
+            (AddressKind::Unnamed, AddressKind::Unnamed) => true,
+            (AddressKind::Pathname(a), AddressKind::Pathname(b)) => a == b,
+            (AddressKind::Abstract(a), AddressKind::Abstract(b)) => a == b,
+            _ => false,
+        }
+    }
+
+    /// Creates an address for the socket file at `path`.
+    ///
+    /// Returns an error if the path is not valid UTF-8, contains null bytes,
+    /// or is too long to fit in a `sockaddr_un`.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::os::windows::net::{SocketAddr, UnixListener};
+    ///
+    /// let socket = UnixListener::bind("/tmp/sock").unwrap();
+    /// let addr = socket.local_addr().expect("Couldn't get local address");
+    /// assert_eq!(addr, SocketAddr::from_pathname("/tmp/sock").unwrap());
+    /// ```
+    pub fn from_pathname<P: AsRef<Path>>(path: P) -> io::Result<SocketAddr> {
+        fn inner(path: &Path) -> io::Result<SocketAddr> {
+            let (addr, len) = unsafe { sockaddr_un(path)? };
+            SocketAddr::from_parts(addr, len)
+        }
+        inner(path.as_ref())
+    }
+
     /// Returns true if and only if the address is unnamed.
     ///
     /// # Examples
@@ -235,14 +291,77 @@ impl SocketAddr {
         }
     }
 
+    /// Creates an address in the abstract namespace.
+    ///
+    /// Abstract names don't appear in the file system, so there is no socket
+    /// file to clean up once the socket is closed. The name may contain any
+    /// bytes, including nulls, as long as it isn't made up of nulls only.
+    ///
+    /// Returns an error if the name is too long to fit in a `sockaddr_un`, or
+    /// if it is empty or all nulls: such a name can't be told apart from an
+    /// unnamed address.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::os::windows::net::{SocketAddr, UnixListener};
+    ///
+    /// let addr = SocketAddr::from_abstract_name(b"hidden").unwrap();
+    /// let listener = UnixListener::bind_addr(&addr).unwrap();
+    /// ```
+    pub fn from_abstract_name<N: AsRef<[u8]>>(name: N) -> io::Result<SocketAddr> {
+        let name = name.as_ref();
+        let mut addr: netc::sockaddr_un = unsafe { mem::zeroed() };
+        addr.sun_family = AF_UNIX as netc::sa_family_t;
+
+        // The leading null byte of `sun_path` marks the name as abstract
+        if name.len() >= addr.sun_path.len() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                      "abstract name must be shorter than SUN_LEN"));
+        }
+        if name.iter().all(|&b| b == 0) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                      "abstract name must contain a non-null byte"));
+        }
+        for (dst, src) in addr.sun_path[1..].iter_mut().zip(name.iter()) {
+            *dst = *src as libc::c_char;
+        }
+
+        let len = sun_path_offset() + 1 + name.len();
+        SocketAddr::from_parts(addr, len as libc::c_int)
+    }
+
+    /// Returns the contents of this address if it is an abstract address.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::os::windows::net::{SocketAddr, UnixListener};
+    ///
+    /// let addr = SocketAddr::from_abstract_name(b"hidden").unwrap();
+    /// let listener = UnixListener::bind_addr(&addr).unwrap();
+    /// let addr = listener.local_addr().expect("Couldn't get local address");
+    /// assert_eq!(addr.as_abstract_name(), Some(&b"hidden"[..]));
+    /// ```
+    pub fn as_abstract_name(&self) -> Option<&[u8]> {
+        if let AddressKind::Abstract(name) = self.address() {
+            Some(name)
+        } else {
+            None
+        }
+    }
+
     fn address<'a>(&'a self) -> AddressKind<'a> {
         let len = self.len as usize - sun_path_offset();
         // sockaddr_un::sun_path on Windows is a Win32 UTF-8 file system path
         let path = unsafe { mem::transmute::<&[libc::c_char], &[u8]>(&self.addr.sun_path) };
 
-        // macOS seems to return a len of 16 and a zeroed sun_path for unnamed addresses
+        // macOS seems to return a len of 16 and a zeroed sun_path for unnamed addresses.
+        // Windows does the same, so `from_abstract_name` refuses names made up
+        // only of null bytes, which couldn't be told apart from it.
         if len == 0
-            || (cfg!(not(any(target_os = "linux", target_os = "android")))
+            || path[..len].iter().all(|&b| b == 0)
+            || (cfg!(not(any(target_os = "linux", target_os = "android", windows)))
                 && self.addr.sun_path[0] == 0)
         {
             AddressKind::Unnamed
@@ -267,6 +386,23 @@ impl fmt::Debug for SocketAddr {
     }
 }
 
+// Only the first `len` bytes of the address are meaningful; whatever follows
+// in `sun_path` is left over from the system call which filled it in.
+impl PartialEq for SocketAddr {
+    fn eq(&self, other: &SocketAddr) -> bool {
+        self.addr.sun_family == other.addr.sun_family && self.sun_path() == other.sun_path()
+    }
+}
+
+impl Eq for SocketAddr {}
+
+impl Hash for SocketAddr {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.addr.sun_family.hash(state);
+        self.sun_path().hash(state);
+    }
+}
+
 struct AsciiEscaped<'a>(&'a [u8]);
 
 impl<'a> fmt::Display for AsciiEscaped<'a> {
@@ -279,6 +415,22 @@ impl<'a> fmt::Display for AsciiEscaped<'a> {
     }
 }
 
+/// Credentials of the process on the other end of a [`UnixStream`].
+///
+/// Which fields are filled in depends on the platform. Windows only reports
+/// the peer's process id, so `uid` and `gid` are always `None` there.
+///
+/// [`UnixStream`]: struct.UnixStream.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct UCred {
+    /// The process id of the peer.
+    pub pid: u32,
+    /// The user id of the peer, where available.
+    pub uid: Option<u32>,
+    /// The group id of the peer, where available.
+    pub gid: Option<u32>,
+}
+
 /// A Unix stream socket.
 ///
 /// # Examples
@@ -331,17 +483,77 @@ impl UnixStream {
     pub fn connect<P: AsRef<Path>>(path: P) -> io::Result<UnixStream> {
         init();
         fn inner(path: &Path) -> io::Result<UnixStream> {
-            unsafe {
-                let inner = Socket::new_raw(AF_UNIX, SOCK_STREAM)?;
-                let (addr, len) = sockaddr_un(path)?;
-
-                cvt(connect(*inner.as_inner() as usize, &addr as *const _ as *const _, len as i32))?;
-                Ok(UnixStream(inner))
-            }
+            let (addr, len) = unsafe { sockaddr_un(path)? };
+            UnixStream::connect_sockaddr(&addr, len)
         }
         inner(path.as_ref())
     }
 
+    /// Connects to the socket specified by [`address`].
+    ///
+    /// Unlike [`connect`], this can reach sockets in the abstract namespace.
+    ///
+    /// [`address`]: struct.SocketAddr.html
+    /// [`connect`]: #method.connect
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::os::windows::net::{SocketAddr, UnixStream};
+    ///
+    /// let addr = SocketAddr::from_abstract_name(b"hidden").unwrap();
+    /// let socket = match UnixStream::connect_addr(&addr) {
+    ///     Ok(sock) => sock,
+    ///     Err(e) => {
+    ///         println!("Couldn't connect: {:?}", e);
+    ///         return
+    ///     }
+    /// };
+    /// ```
+    pub fn connect_addr(socket_addr: &SocketAddr) -> io::Result<UnixStream> {
+        init();
+        UnixStream::connect_sockaddr(&socket_addr.addr, socket_addr.len)
+    }
+
+    /// Connects to the socket named by `path`, failing with a `TimedOut`
+    /// error if the connection isn't established within `timeout`.
+    ///
+    /// It is an error to pass a zero `Duration` to this function.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::os::windows::net::UnixStream;
+    /// use std::time::Duration;
+    ///
+    /// let socket = match UnixStream::connect_timeout("/tmp/sock", Duration::from_secs(1)) {
+    ///     Ok(sock) => sock,
+    ///     Err(e) => {
+    ///         println!("Couldn't connect: {:?}", e);
+    ///         return
+    ///     }
+    /// };
+    /// ```
+    pub fn connect_timeout<P: AsRef<Path>>(path: P, timeout: Duration) -> io::Result<UnixStream> {
+        init();
+        fn inner(path: &Path, timeout: Duration) -> io::Result<UnixStream> {
+            let (addr, len) = unsafe { sockaddr_un(path)? };
+            let inner = Socket::new_raw(AF_UNIX, SOCK_STREAM)?;
+            inner.connect_raw_timeout(&addr as *const _ as *const _, len, timeout)?;
+            Ok(UnixStream(inner))
+        }
+        inner(path.as_ref(), timeout)
+    }
+
+    fn connect_sockaddr(addr: &netc::sockaddr_un, len: libc::c_int) -> io::Result<UnixStream> {
+        unsafe {
+            let inner = Socket::new_raw(AF_UNIX, SOCK_STREAM)?;
+
+            cvt(connect(*inner.as_inner() as usize, addr as *const _ as *const _, len as i32))?;
+            Ok(UnixStream(inner))
+        }
+    }
+
     /// Creates an unnamed pair of connected sockets.
     ///
     /// Returns two `UnixStream`s which are connected to each other.
@@ -360,12 +572,19 @@ impl UnixStream {
     /// };
     /// ```
     // #[stable(feature = "unix_socket", since = "1.10.0")]
-    // Windows dosn't support socketpair()...this would need to be emulated
-    // pub fn pair() -> io::Result<(UnixStream, UnixStream)> {
-    //     init();
-    //     let (i1, i2) = Socket::new_pair(AF_UNIX, SOCK_STREAM)?;
-    //     Ok((UnixStream(i1), UnixStream(i2)))
-    // }
+    pub fn pair() -> io::Result<(UnixStream, UnixStream)> {
+        init();
+        // Windows doesn't support socketpair(), so rendezvous through a
+        // listener on a temporary path which is removed again as soon as
+        // both ends are connected.
+        let (listener, path) = bind_temp(|path| UnixListener::bind(path))?;
+        let pair = UnixStream::connect(&path).and_then(|s1| {
+            listener.accept().map(|(s2, _)| (s1, s2))
+        });
+        drop(listener);
+        let _ = fs::remove_file(&path);
+        pair
+    }
 
     /// Creates a new independently owned handle to the underlying socket.
     ///
@@ -417,6 +636,99 @@ impl UnixStream {
         SocketAddr::new(|addr, len| unsafe { getpeername(*self.0.as_inner() as usize, addr, len) })
     }
 
+    /// Returns the credentials of the process on the remote half of this
+    /// connection.
+    ///
+    /// The credentials are those the peer had when the connection was
+    /// established.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::os::windows::net::UnixStream;
+    ///
+    /// let socket = UnixStream::connect("/tmp/sock").unwrap();
+    /// let cred = socket.peer_cred().expect("Couldn't get peer credentials");
+    /// println!("connected to process {}", cred.pid);
+    /// ```
+    pub fn peer_cred(&self) -> io::Result<UCred> {
+        let mut pid: netc::ULONG = 0;
+        self.0.ioctl(netc::SIO_AF_UNIX_GETPEERPID, &(), &mut pid)?;
+        Ok(UCred { pid: pid as u32, uid: None, gid: None })
+    }
+
+    /// Sends `socket` to the process on the other end of this connection,
+    /// which takes it over with [`recv_socket`].
+    ///
+    /// The socket is duplicated for the peer, so `socket` itself stays open
+    /// in this process. What goes over the connection is the description of
+    /// the duplicate, in band with any other data, so the peer has to know
+    /// when to call [`recv_socket`].
+    ///
+    /// [`recv_socket`]: #method.recv_socket
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::TcpListener;
+    /// use std::os::windows::net::UnixStream;
+    ///
+    /// let worker = UnixStream::connect("/tmp/worker").unwrap();
+    /// let listener = TcpListener::bind("127.0.0.1:8080").unwrap();
+    /// for stream in listener.incoming() {
+    ///     worker.send_socket(&stream.unwrap()).expect("Couldn't hand over connection");
+    /// }
+    /// ```
+    pub fn send_socket<S: AsRawSocket>(&self, socket: &S) -> io::Result<()> {
+        let pid = self.peer_cred()?.pid;
+        let info = Socket::duplicate_for(socket.as_raw_socket() as netc::SOCKET,
+                                         pid as netc::DWORD)?;
+        let buf = unsafe {
+            slice::from_raw_parts(&info as *const _ as *const u8, mem::size_of_val(&info))
+        };
+        (&*self).write_all(buf)
+    }
+
+    /// Receives a socket sent by the process on the other end of this
+    /// connection with [`send_socket`].
+    ///
+    /// [`send_socket`]: #method.send_socket
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::net::TcpStream;
+    /// use std::os::windows::net::UnixStream;
+    ///
+    /// let front_door = UnixStream::connect("/tmp/front-door").unwrap();
+    /// let stream: TcpStream = front_door.recv_socket().expect("Couldn't receive connection");
+    /// ```
+    pub fn recv_socket<S: FromRawSocket>(&self) -> io::Result<S> {
+        let mut info: netc::WSAPROTOCOL_INFO = unsafe { mem::zeroed() };
+        {
+            let buf = unsafe {
+                slice::from_raw_parts_mut(&mut info as *mut _ as *mut u8,
+                                          mem::size_of_val(&info))
+            };
+            // Only a complete description may reach `WSASocketW`
+            let mut nread = 0;
+            while nread < buf.len() {
+                match (&*self).read(&mut buf[nread..]) {
+                    Ok(0) => break,
+                    Ok(n) => nread += n,
+                    Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                    Err(e) => return Err(e),
+                }
+            }
+            if nread < buf.len() {
+                return Err(io::Error::new(io::ErrorKind::InvalidData,
+                                          "connection closed before a whole socket was received"));
+            }
+        }
+        let socket = Socket::from_protocol_info(&mut info)?;
+        Ok(unsafe { S::from_raw_socket(socket.into_inner() as RawSocket) })
+    }
+
     /// Sets the read timeout for the socket.
     ///
     /// If the provided value is [`None`], then [`read`] calls will block
@@ -564,6 +876,66 @@ impl UnixStream {
         self.0.take_error()
     }
 
+    /// Sets the value of the `SO_LINGER` option on this socket.
+    ///
+    /// With a linger time set, closing the socket blocks until pending data
+    /// has been sent or the time runs out. A linger time of zero makes closing
+    /// the socket reset the connection instead; see
+    /// [`close_abortive`][close_abortive]. `None` restores the default
+    /// behavior. Linger times are rounded up to whole seconds.
+    ///
+    /// [close_abortive]: #method.close_abortive
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::os::windows::net::UnixStream;
+    /// use std::time::Duration;
+    ///
+    /// let socket = UnixStream::connect("/tmp/sock").unwrap();
+    /// socket.set_linger(Some(Duration::from_secs(5))).expect("Couldn't set linger");
+    /// ```
+    pub fn set_linger(&self, linger: Option<Duration>) -> io::Result<()> {
+        self.0.set_linger(linger)
+    }
+
+    /// Returns the value of the `SO_LINGER` option on this socket.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::os::windows::net::UnixStream;
+    /// use std::time::Duration;
+    ///
+    /// let socket = UnixStream::connect("/tmp/sock").unwrap();
+    /// socket.set_linger(Some(Duration::from_secs(5))).expect("Couldn't set linger");
+    /// assert_eq!(socket.linger().unwrap(), Some(Duration::from_secs(5)));
+    /// ```
+    pub fn linger(&self) -> io::Result<Option<Duration>> {
+        self.0.linger()
+    }
+
+    /// Closes the connection, discarding any data that hasn't been sent yet.
+    /// The peer sees the connection being reset.
+    ///
+    /// If the socket has been cloned with [`try_clone`][link], the connection
+    /// is only reset once every handle to it has been closed.
+    ///
+    /// [link]: #method.try_clone
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::os::windows::net::UnixStream;
+    ///
+    /// let socket = UnixStream::connect("/tmp/sock").unwrap();
+    /// socket.close_abortive().expect("Couldn't close the socket");
+    /// ```
+    pub fn close_abortive(self) -> io::Result<()> {
+        // The socket is closed when `self` is dropped on return.
+        self.set_linger(Some(Duration::from_secs(0)))
+    }
+
     /// Shuts down the read, write, or both halves of this connection.
     ///
     /// This function will cause all pending and future I/O calls on the
@@ -585,6 +957,38 @@ impl UnixStream {
     pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
         self.0.shutdown(how)
     }
+
+    /// Reads into each buffer of `bufs` in turn with a single system call,
+    /// returning the total number of bytes read.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::os::windows::net::UnixStream;
+    ///
+    /// let socket = UnixStream::connect("/tmp/sock").unwrap();
+    /// let mut header = [0; 4];
+    /// let mut body = [0; 256];
+    /// let n = socket.read_vectored(&mut [&mut header, &mut body]).unwrap();
+    /// ```
+    pub fn read_vectored(&self, bufs: &mut [&mut [u8]]) -> io::Result<usize> {
+        self.0.read_vectored(bufs)
+    }
+
+    /// Writes each buffer of `bufs` in turn with a single system call,
+    /// returning the total number of bytes written.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::os::windows::net::UnixStream;
+    ///
+    /// let socket = UnixStream::connect("/tmp/sock").unwrap();
+    /// let n = socket.write_vectored(&[b"head", b"body"]).unwrap();
+    /// ```
+    pub fn write_vectored(&self, bufs: &[&[u8]]) -> io::Result<usize> {
+        self.0.write_vectored(bufs)
+    }
 }
 
 // #[stable(feature = "unix_socket", since = "1.10.0")]
@@ -640,84 +1044,90 @@ impl<'a> io::Write for &'a UnixStream {
     }
 }
 
-// // #[stable(feature = "unix_socket", since = "1.10.0")]
-// impl AsRawFd for UnixStream {
-//     fn as_raw_fd(&self) -> RawFd {
-//         *self.0.as_inner()
-//     }
-// }
+// #[stable(feature = "unix_socket", since = "1.10.0")]
+impl AsRawSocket for UnixStream {
+    fn as_raw_socket(&self) -> RawSocket {
+        *self.0.as_inner() as RawSocket
+    }
+}
 
-// // #[stable(feature = "unix_socket", since = "1.10.0")]
-// impl FromRawFd for UnixStream {
-//     unsafe fn from_raw_fd(fd: RawFd) -> UnixStream {
-//         UnixStream(Socket::from_inner(fd))
-//     }
-// }
+// #[stable(feature = "unix_socket", since = "1.10.0")]
+impl FromRawSocket for UnixStream {
+    unsafe fn from_raw_socket(sock: RawSocket) -> UnixStream {
+        UnixStream(Socket::from_inner(sock as netc::SOCKET))
+    }
+}
 
-// // #[stable(feature = "unix_socket", since = "1.10.0")]
-// impl IntoRawFd for UnixStream {
-//     fn into_raw_fd(self) -> RawFd {
-//         self.0.into_inner()
-//     }
-// }
+// #[stable(feature = "unix_socket", since = "1.10.0")]
+impl IntoRawSocket for UnixStream {
+    fn into_raw_socket(self) -> RawSocket {
+        self.0.into_inner() as RawSocket
+    }
+}
 
-// // #[stable(feature = "rust1", since = "1.0.0")]
-// impl AsRawFd for net::TcpStream {
-//     fn as_raw_fd(&self) -> RawFd { *self.as_inner().socket().as_inner() }
-// }
+// #[stable(feature = "rust1", since = "1.0.0")]
+impl AsRawSocket for net::TcpStream {
+    fn as_raw_socket(&self) -> RawSocket {
+        *self.as_inner().socket().as_inner() as RawSocket
+    }
+}
 
-// // #[stable(feature = "rust1", since = "1.0.0")]
-// impl AsRawFd for net::TcpListener {
-//     fn as_raw_fd(&self) -> RawFd { *self.as_inner().socket().as_inner() }
-// }
+// #[stable(feature = "rust1", since = "1.0.0")]
+impl AsRawSocket for net::TcpListener {
+    fn as_raw_socket(&self) -> RawSocket {
+        *self.as_inner().socket().as_inner() as RawSocket
+    }
+}
 
-// // #[stable(feature = "rust1", since = "1.0.0")]
-// impl AsRawFd for net::UdpSocket {
-//     fn as_raw_fd(&self) -> RawFd { *self.as_inner().socket().as_inner() }
-// }
+// #[stable(feature = "rust1", since = "1.0.0")]
+impl AsRawSocket for net::UdpSocket {
+    fn as_raw_socket(&self) -> RawSocket {
+        *self.as_inner().socket().as_inner() as RawSocket
+    }
+}
 
-// // #[stable(feature = "from_raw_os", since = "1.1.0")]
-// impl FromRawFd for net::TcpStream {
-//     unsafe fn from_raw_fd(fd: RawFd) -> net::TcpStream {
-//         let socket = sys::net::Socket::from_inner(fd);
-//         net::TcpStream::from_inner(sys_common::net::TcpStream::from_inner(socket))
-//     }
-// }
+// #[stable(feature = "from_raw_os", since = "1.1.0")]
+impl FromRawSocket for net::TcpStream {
+    unsafe fn from_raw_socket(sock: RawSocket) -> net::TcpStream {
+        let socket = Socket::from_inner(sock as netc::SOCKET);
+        net::TcpStream::from_inner(sys_common::net::TcpStream::from_inner(socket))
+    }
+}
 
-// // #[stable(feature = "from_raw_os", since = "1.1.0")]
-// impl FromRawFd for net::TcpListener {
-//     unsafe fn from_raw_fd(fd: RawFd) -> net::TcpListener {
-//         let socket = sys::net::Socket::from_inner(fd);
-//         net::TcpListener::from_inner(sys_common::net::TcpListener::from_inner(socket))
-//     }
-// }
+// #[stable(feature = "from_raw_os", since = "1.1.0")]
+impl FromRawSocket for net::TcpListener {
+    unsafe fn from_raw_socket(sock: RawSocket) -> net::TcpListener {
+        let socket = Socket::from_inner(sock as netc::SOCKET);
+        net::TcpListener::from_inner(sys_common::net::TcpListener::from_inner(socket))
+    }
+}
 
-// // #[stable(feature = "from_raw_os", since = "1.1.0")]
-// impl FromRawFd for net::UdpSocket {
-//     unsafe fn from_raw_fd(fd: RawFd) -> net::UdpSocket {
-//         let socket = sys::net::Socket::from_inner(fd);
-//         net::UdpSocket::from_inner(sys_common::net::UdpSocket::from_inner(socket))
-//     }
-// }
+// #[stable(feature = "from_raw_os", since = "1.1.0")]
+impl FromRawSocket for net::UdpSocket {
+    unsafe fn from_raw_socket(sock: RawSocket) -> net::UdpSocket {
+        let socket = Socket::from_inner(sock as netc::SOCKET);
+        net::UdpSocket::from_inner(sys_common::net::UdpSocket::from_inner(socket))
+    }
+}
 
-// // #[stable(feature = "into_raw_os", since = "1.4.0")]
-// impl IntoRawFd for net::TcpStream {
-//     fn into_raw_fd(self) -> RawFd {
-//         self.into_inner().into_socket().into_inner()
-//     }
-// }
-// // #[stable(feature = "into_raw_os", since = "1.4.0")]
-// impl IntoRawFd for net::TcpListener {
-//     fn into_raw_fd(self) -> RawFd {
-//         self.into_inner().into_socket().into_inner()
-//     }
-// }
-// // #[stable(feature = "into_raw_os", since = "1.4.0")]
-// impl IntoRawFd for net::UdpSocket {
-//     fn into_raw_fd(self) -> RawFd {
-//         self.into_inner().into_socket().into_inner()
-//     }
-// }
+// #[stable(feature = "into_raw_os", since = "1.4.0")]
+impl IntoRawSocket for net::TcpStream {
+    fn into_raw_socket(self) -> RawSocket {
+        self.into_inner().into_socket().into_inner() as RawSocket
+    }
+}
+// #[stable(feature = "into_raw_os", since = "1.4.0")]
+impl IntoRawSocket for net::TcpListener {
+    fn into_raw_socket(self) -> RawSocket {
+        self.into_inner().into_socket().into_inner() as RawSocket
+    }
+}
+// #[stable(feature = "into_raw_os", since = "1.4.0")]
+impl IntoRawSocket for net::UdpSocket {
+    fn into_raw_socket(self) -> RawSocket {
+        self.into_inner().into_socket().into_inner() as RawSocket
+    }
+}
 
 /// A structure representing a Unix domain socket server.
 ///
@@ -748,19 +1158,100 @@ impl<'a> io::Write for &'a UnixStream {
 /// }
 /// ```
 // #[stable(feature = "unix_socket", since = "1.10.0")]
-pub struct UnixListener(Socket);
+pub struct UnixListener {
+    inner: Socket,
+    socket_file: Arc<SocketFile>,
+    // Winsock can't report whether a socket is nonblocking, and
+    // `accept_timeout` has to put the listener back the way it found it.
+    nonblocking: Arc<AtomicBool>,
+}
 
-// #[stable(feature = "unix_socket", since = "1.10.0")]
-impl fmt::Debug for UnixListener {
-    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        let mut builder = fmt.debug_struct("UnixListener");
-        builder.field("fd", self.0.as_inner());
-        if let Ok(addr) = self.local_addr() {
-            builder.field("local", &addr);
-        }
-        builder.finish()
+// The socket file which a listener and all of its clones remove once the last
+// of them is dropped, if `UnixListener::set_unlink_on_drop` asked for that.
+struct SocketFile(Mutex<Option<PathBuf>>);
+
+impl SocketFile {
+    fn new() -> Arc<SocketFile> {
+        Arc::new(SocketFile(Mutex::new(None)))
     }
-}
+
+    fn set(&self, path: Option<PathBuf>) {
+        *self.0.lock().unwrap() = path;
+    }
+}
+
+impl Drop for SocketFile {
+    fn drop(&mut self) {
+        if let Some(ref path) = *self.0.lock().unwrap() {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+// A socket file is stale if nothing accepts connections on it any more, which
+// is what's left behind when a listener's process exits without removing it.
+fn is_stale(path: &Path) -> bool {
+    match UnixStream::connect(path) {
+        Err(ref e) => e.kind() == io::ErrorKind::ConnectionRefused,
+        Ok(_) => false,
+    }
+}
+
+// Removes the file at `path` if it is a stale socket file, returning whether
+// it did. Anything that isn't an AF_UNIX socket file is left alone.
+//
+// The file is opened before it is checked and deleted through that same
+// handle, so what gets deleted is the file that was checked, even if the name
+// has been pointed at another file in the meantime. The handle shares access
+// (probing the socket needs to open the file again), so this doesn't stop a
+// socket being bound there between the check and the delete; it only
+// guarantees a different file is never removed.
+fn remove_stale(path: &Path) -> io::Result<bool> {
+    use std::os::windows::fs::OpenOptionsExt;
+    use std::os::windows::io::AsRawHandle;
+    use sys;
+
+    let file = fs::OpenOptions::new()
+        .access_mode(netc::DELETE | netc::FILE_READ_ATTRIBUTES)
+        .custom_flags(netc::FILE_FLAG_OPEN_REPARSE_POINT | netc::FILE_FLAG_BACKUP_SEMANTICS)
+        .open(path)?;
+    let handle = file.as_raw_handle() as netc::HANDLE;
+
+    let mut info: netc::FILE_ATTRIBUTE_TAG_INFO = unsafe { mem::zeroed() };
+    sys::cvt(unsafe {
+        netc::GetFileInformationByHandleEx(handle,
+                                           netc::FileAttributeTagInfo,
+                                           &mut info as *mut _ as netc::LPVOID,
+                                           mem::size_of_val(&info) as netc::DWORD)
+    })?;
+    if info.FileAttributes & netc::FILE_ATTRIBUTE_REPARSE_POINT == 0
+        || info.ReparseTag != netc::IO_REPARSE_TAG_AF_UNIX
+        || !is_stale(path)
+    {
+        return Ok(false)
+    }
+
+    let mut disposition = netc::FILE_DISPOSITION_INFO { DeleteFile: 1 };
+    sys::cvt(unsafe {
+        netc::SetFileInformationByHandle(handle,
+                                         netc::FileDispositionInfo,
+                                         &mut disposition as *mut _ as netc::LPVOID,
+                                         mem::size_of_val(&disposition) as netc::DWORD)
+    })?;
+    Ok(true)
+}
+
+// #[stable(feature = "unix_socket", since = "1.10.0")]
+impl fmt::Debug for UnixListener {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let mut builder = fmt.debug_struct("UnixListener");
+        builder.field("fd", self.inner.as_inner());
+        if let Ok(addr) = self.local_addr() {
+            builder.field("local", &addr);
+        }
+        builder.finish()
+    }
+}
 
 impl UnixListener {
     /// Creates a new `UnixListener` bound to the specified socket.
@@ -780,19 +1271,59 @@ impl UnixListener {
     /// ```
     // #[stable(feature = "unix_socket", since = "1.10.0")]
     pub fn bind<P: AsRef<Path>>(path: P) -> io::Result<UnixListener> {
-        init();
-        fn inner(path: &Path) -> io::Result<UnixListener> {
-            unsafe {
-                let inner = Socket::new_raw(AF_UNIX, SOCK_STREAM)?;
-                let (addr, len) = sockaddr_un(path)?;
+        UnixListenerBuilder::new().bind(path)
+    }
 
-                cvt(bind(*inner.as_inner() as usize, &addr as *const _ as *const _, len as _))?;
-                cvt(listen(*inner.as_inner() as usize, 128))?;
+    /// Creates a new `UnixListener` bound to the specified [`address`].
+    ///
+    /// Unlike [`bind`], this can bind to a name in the abstract namespace,
+    /// which leaves no socket file behind.
+    ///
+    /// [`address`]: struct.SocketAddr.html
+    /// [`bind`]: #method.bind
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::os::windows::net::{SocketAddr, UnixListener};
+    ///
+    /// let addr = SocketAddr::from_abstract_name(b"hidden").unwrap();
+    /// let listener = match UnixListener::bind_addr(&addr) {
+    ///     Ok(sock) => sock,
+    ///     Err(e) => {
+    ///         println!("Couldn't bind: {:?}", e);
+    ///         return
+    ///     }
+    /// };
+    /// ```
+    pub fn bind_addr(socket_addr: &SocketAddr) -> io::Result<UnixListener> {
+        UnixListenerBuilder::new().bind_addr(socket_addr)
+    }
 
-                Ok(UnixListener(inner))
-            }
-        }
-        inner(path.as_ref())
+    /// Creates a new `UnixListener` bound to the specified socket, taking over
+    /// the socket file if it was left behind by a listener which is gone.
+    ///
+    /// If binding fails because `path` already exists, this tries to connect
+    /// to it. The file is only removed, and binding retried, if it is a socket
+    /// file and that connection is refused; a path which a live listener still
+    /// accepts connections on, or which isn't a socket file at all, keeps
+    /// failing with `AddrInUse`.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::os::windows::net::UnixListener;
+    ///
+    /// let listener = match UnixListener::bind_reclaim("/path/to/the/socket") {
+    ///     Ok(sock) => sock,
+    ///     Err(e) => {
+    ///         println!("Couldn't bind: {:?}", e);
+    ///         return
+    ///     }
+    /// };
+    /// ```
+    pub fn bind_reclaim<P: AsRef<Path>>(path: P) -> io::Result<UnixListener> {
+        UnixListenerBuilder::new().reclaim_stale(true).bind(path)
     }
 
     /// Accepts a new incoming connection to this listener.
@@ -819,11 +1350,76 @@ impl UnixListener {
     pub fn accept(&self) -> io::Result<(UnixStream, SocketAddr)> {
         let mut storage: netc::sockaddr_un = unsafe { mem::zeroed() };
         let mut len = mem::size_of_val(&storage) as libc::c_int;
-        let sock = self.0.accept(&mut storage as *mut _ as *mut _, &mut len)?;
+        let sock = self.inner.accept(&mut storage as *mut _ as *mut _, &mut len)?;
         let addr = SocketAddr::from_parts(storage, len)?;
         Ok((UnixStream(sock), addr))
     }
 
+    /// Accepts a new incoming connection to this listener, failing with a
+    /// `TimedOut` error if none arrives within `timeout`.
+    ///
+    /// It is an error to pass a zero `Duration` to this function.
+    ///
+    /// The listener is briefly put in nonblocking mode to accept the
+    /// connection, so that one taken by another handle to the listener in the
+    /// meantime doesn't leave this call blocked past its timeout. A blocking
+    /// [`accept`] on another handle at the same moment may fail with
+    /// `WouldBlock` because of that.
+    ///
+    /// [`accept`]: #method.accept
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::os::windows::net::UnixListener;
+    /// use std::time::Duration;
+    ///
+    /// let listener = UnixListener::bind("/path/to/the/socket").unwrap();
+    ///
+    /// match listener.accept_timeout(Duration::from_secs(1)) {
+    ///     Ok((socket, addr)) => println!("Got a client: {:?}", addr),
+    ///     Err(e) => println!("accept function failed: {:?}", e),
+    /// }
+    /// ```
+    pub fn accept_timeout(&self, timeout: Duration) -> io::Result<(UnixStream, SocketAddr)> {
+        let start = Instant::now();
+        let mut remaining = timeout;
+        loop {
+            self.inner.wait_readable(remaining)?;
+            match self.accept_nonblocking() {
+                Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {}
+                res => return res,
+            }
+
+            // Someone else got the connection, wait for another one
+            let elapsed = start.elapsed();
+            if elapsed >= timeout {
+                return Err(io::Error::new(io::ErrorKind::TimedOut, "operation timed out"));
+            }
+            remaining = timeout - elapsed;
+        }
+    }
+
+    fn accept_nonblocking(&self) -> io::Result<(UnixStream, SocketAddr)> {
+        if self.nonblocking.load(Ordering::SeqCst) {
+            return self.accept()
+        }
+
+        self.inner.set_nonblocking(true)?;
+        let res = self.accept();
+        // The listener is put back before anything can fail. If even that
+        // fails, it is recorded as nonblocking rather than losing the
+        // connection that was just accepted.
+        if self.inner.set_nonblocking(false).is_err() {
+            self.nonblocking.store(true, Ordering::SeqCst);
+        }
+        let (stream, addr) = res?;
+        // Sockets accepted on a nonblocking listener are nonblocking as well.
+        // The connection is handed over even if that can't be undone.
+        let _ = stream.set_nonblocking(false);
+        Ok((stream, addr))
+    }
+
     /// Creates a new independently owned handle to the underlying socket.
     ///
     /// The returned `UnixListener` is a reference to the same socket that this
@@ -841,7 +1437,12 @@ impl UnixListener {
     /// ```
     // #[stable(feature = "unix_socket", since = "1.10.0")]
     pub fn try_clone(&self) -> io::Result<UnixListener> {
-        self.0.duplicate().map(UnixListener)
+        let inner = self.inner.duplicate()?;
+        Ok(UnixListener {
+            inner,
+            socket_file: self.socket_file.clone(),
+            nonblocking: self.nonblocking.clone(),
+        })
     }
 
     /// Returns the local socket address of this listener.
@@ -857,7 +1458,7 @@ impl UnixListener {
     /// ```
     // #[stable(feature = "unix_socket", since = "1.10.0")]
     pub fn local_addr(&self) -> io::Result<SocketAddr> {
-        SocketAddr::new(|addr, len| unsafe { getsockname(*self.0.as_inner() as usize, addr, len) })
+        SocketAddr::new(|addr, len| unsafe { getsockname(*self.inner.as_inner() as usize, addr, len) })
     }
 
     /// Moves the socket into or out of nonblocking mode.
@@ -873,7 +1474,9 @@ impl UnixListener {
     /// ```
     // #[stable(feature = "unix_socket", since = "1.10.0")]
     pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
-        self.0.set_nonblocking(nonblocking)
+        self.inner.set_nonblocking(nonblocking)?;
+        self.nonblocking.store(nonblocking, Ordering::SeqCst);
+        Ok(())
     }
 
     /// Returns the value of the `SO_ERROR` option.
@@ -894,7 +1497,38 @@ impl UnixListener {
     /// On Redox this always returns None.
     // #[stable(feature = "unix_socket", since = "1.10.0")]
     pub fn take_error(&self) -> io::Result<Option<io::Error>> {
-        self.0.take_error()
+        self.inner.take_error()
+    }
+
+    /// Sets whether the socket file this listener is bound to is removed once
+    /// the listener, and every handle created by [`try_clone`], is dropped.
+    ///
+    /// Returns an `InvalidInput` error when enabling this for a listener which
+    /// isn't bound to a pathname.
+    ///
+    /// [`try_clone`]: #method.try_clone
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::os::windows::net::UnixListener;
+    ///
+    /// let listener = UnixListener::bind("/path/to/the/socket").unwrap();
+    /// listener.set_unlink_on_drop(true).expect("Couldn't set unlink on drop");
+    /// ```
+    pub fn set_unlink_on_drop(&self, unlink: bool) -> io::Result<()> {
+        let path = if unlink {
+            let addr = self.local_addr()?;
+            match addr.as_pathname() {
+                Some(path) => Some(path.to_path_buf()),
+                None => return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                                  "listener is not bound to a pathname")),
+            }
+        } else {
+            None
+        };
+        self.socket_file.set(path);
+        Ok(())
     }
 
     /// Returns an iterator over incoming connections.
@@ -934,26 +1568,32 @@ impl UnixListener {
     }
 }
 
-// // #[stable(feature = "unix_socket", since = "1.10.0")]
-// impl AsRawFd for UnixListener {
-//     fn as_raw_fd(&self) -> RawFd {
-//         *self.0.as_inner()
-//     }
-// }
+// #[stable(feature = "unix_socket", since = "1.10.0")]
+impl AsRawSocket for UnixListener {
+    fn as_raw_socket(&self) -> RawSocket {
+        *self.inner.as_inner() as RawSocket
+    }
+}
 
-// // #[stable(feature = "unix_socket", since = "1.10.0")]
-// impl FromRawFd for UnixListener {
-//     unsafe fn from_raw_fd(fd: RawFd) -> UnixListener {
-//         UnixListener(Socket::from_inner(fd))
-//     }
-// }
+// #[stable(feature = "unix_socket", since = "1.10.0")]
+impl FromRawSocket for UnixListener {
+    unsafe fn from_raw_socket(sock: RawSocket) -> UnixListener {
+        UnixListener {
+            inner: Socket::from_inner(sock as netc::SOCKET),
+            socket_file: SocketFile::new(),
+            nonblocking: Arc::new(AtomicBool::new(false)),
+        }
+    }
+}
 
-// // #[stable(feature = "unix_socket", since = "1.10.0")]
-// impl IntoRawFd for UnixListener {
-//     fn into_raw_fd(self) -> RawFd {
-//         self.0.into_inner()
-//     }
-// }
+// #[stable(feature = "unix_socket", since = "1.10.0")]
+impl IntoRawSocket for UnixListener {
+    fn into_raw_socket(self) -> RawSocket {
+        // Whoever owns the raw socket now is also responsible for its file
+        self.socket_file.set(None);
+        self.inner.into_inner() as RawSocket
+    }
+}
 
 // #[stable(feature = "unix_socket", since = "1.10.0")]
 impl<'a> IntoIterator for &'a UnixListener {
@@ -965,6 +1605,197 @@ impl<'a> IntoIterator for &'a UnixListener {
     }
 }
 
+/// A builder for a [`UnixListener`], which configures the listening socket and
+/// its socket file before any connection can be made to it.
+///
+/// [`UnixListener`]: struct.UnixListener.html
+///
+/// # Examples
+///
+/// Only let the system and administrators connect:
+///
+/// ```ignore
+/// use std::os::windows::net::UnixListenerBuilder;
+///
+/// let listener = UnixListenerBuilder::new()
+///     .backlog(16)
+///     .security_descriptor("D:P(A;;GA;;;SY)(A;;GA;;;BA)")
+///     .create_parent_dirs(true)
+///     .bind("/path/to/the/socket")
+///     .unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct UnixListenerBuilder {
+    backlog: libc::c_int,
+    security_descriptor: Option<String>,
+    create_parent_dirs: bool,
+    reclaim_stale: bool,
+    unlink_on_drop: bool,
+}
+
+impl UnixListenerBuilder {
+    /// Creates a builder with the same settings as [`UnixListener::bind`].
+    ///
+    /// [`UnixListener::bind`]: struct.UnixListener.html#method.bind
+    pub fn new() -> UnixListenerBuilder {
+        UnixListenerBuilder {
+            backlog: 128,
+            security_descriptor: None,
+            create_parent_dirs: false,
+            reclaim_stale: false,
+            unlink_on_drop: false,
+        }
+    }
+
+    /// Sets the maximum number of pending connections passed to `listen`.
+    ///
+    /// The default is 128.
+    pub fn backlog(&mut self, backlog: i32) -> &mut UnixListenerBuilder {
+        self.backlog = backlog;
+        self
+    }
+
+    /// Sets the security descriptor, in SDDL form, whose DACL controls who
+    /// may connect through the socket file.
+    ///
+    /// The DACL is applied before the socket starts listening, so nobody can
+    /// connect while the file still has the access rights it inherited from
+    /// its directory. It has no effect on listeners bound to an abstract name.
+    pub fn security_descriptor(&mut self, sddl: &str) -> &mut UnixListenerBuilder {
+        self.security_descriptor = Some(sddl.to_string());
+        self
+    }
+
+    /// Sets whether missing parent directories of the socket file are
+    /// created.
+    ///
+    /// The default is `false`.
+    pub fn create_parent_dirs(&mut self, create: bool) -> &mut UnixListenerBuilder {
+        self.create_parent_dirs = create;
+        self
+    }
+
+    /// Sets whether a stale socket file at the path is removed, as described
+    /// for [`UnixListener::bind_reclaim`].
+    ///
+    /// The default is `false`.
+    ///
+    /// [`UnixListener::bind_reclaim`]: struct.UnixListener.html#method.bind_reclaim
+    pub fn reclaim_stale(&mut self, reclaim: bool) -> &mut UnixListenerBuilder {
+        self.reclaim_stale = reclaim;
+        self
+    }
+
+    /// Sets whether the listener removes its socket file once dropped, as
+    /// described for [`UnixListener::set_unlink_on_drop`].
+    ///
+    /// The default is `false`.
+    ///
+    /// [`UnixListener::set_unlink_on_drop`]: struct.UnixListener.html#method.set_unlink_on_drop
+    pub fn unlink_on_drop(&mut self, unlink: bool) -> &mut UnixListenerBuilder {
+        self.unlink_on_drop = unlink;
+        self
+    }
+
+    /// Creates a new `UnixListener` bound to the specified socket, with the
+    /// options set on this builder.
+    pub fn bind<P: AsRef<Path>>(&self, path: P) -> io::Result<UnixListener> {
+        init();
+        self._bind(path.as_ref())
+    }
+
+    /// Creates a new `UnixListener` bound to the specified [`address`], with
+    /// the options set on this builder.
+    ///
+    /// [`address`]: struct.SocketAddr.html
+    pub fn bind_addr(&self, socket_addr: &SocketAddr) -> io::Result<UnixListener> {
+        init();
+        match socket_addr.as_pathname() {
+            Some(path) => self._bind(path),
+            None => self.bind_sockaddr(&socket_addr.addr, socket_addr.len, None),
+        }
+    }
+
+    fn _bind(&self, path: &Path) -> io::Result<UnixListener> {
+        if self.create_parent_dirs {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+        }
+
+        let (addr, len) = unsafe { sockaddr_un(path)? };
+        let listener = match self.bind_sockaddr(&addr, len, Some(path)) {
+            // Whatever keeps `remove_stale` from removing the file, the caller
+            // is better served by the original `AddrInUse`.
+            Err(ref e) if self.reclaim_stale
+                && e.kind() == io::ErrorKind::AddrInUse
+                && remove_stale(path).unwrap_or(false) => {
+                self.bind_sockaddr(&addr, len, Some(path))
+            }
+            res => res,
+        }?;
+
+        if self.unlink_on_drop {
+            listener.socket_file.set(Some(path.to_path_buf()));
+        }
+        Ok(listener)
+    }
+
+    fn bind_sockaddr(&self, addr: &netc::sockaddr_un, len: libc::c_int, path: Option<&Path>)
+                     -> io::Result<UnixListener> {
+        unsafe {
+            let inner = Socket::new_raw(AF_UNIX, SOCK_STREAM)?;
+
+            cvt(bind(*inner.as_inner() as usize, addr as *const _ as *const _, len as _))?;
+            if let (Some(path), Some(sddl)) = (path, self.security_descriptor.as_ref()) {
+                if let Err(e) = set_file_security(path, sddl) {
+                    let _ = fs::remove_file(path);
+                    return Err(e);
+                }
+            }
+            cvt(listen(*inner.as_inner() as usize, self.backlog))?;
+
+            Ok(UnixListener {
+                inner,
+                socket_file: SocketFile::new(),
+                nonblocking: Arc::new(AtomicBool::new(false)),
+            })
+        }
+    }
+}
+
+// Replaces the DACL of the socket file at `path` with the one described by the
+// SDDL string `sddl`.
+fn set_file_security(path: &Path, sddl: &str) -> io::Result<()> {
+    use std::os::windows::fs::OpenOptionsExt;
+    use std::os::windows::io::AsRawHandle;
+    use sys;
+
+    let sddl = sys::to_u16s(sddl)?;
+    let mut sd: netc::PSECURITY_DESCRIPTOR = ptr::null_mut();
+    sys::cvt(unsafe {
+        netc::ConvertStringSecurityDescriptorToSecurityDescriptorW(sddl.as_ptr(),
+                                                                   netc::SDDL_REVISION_1,
+                                                                   &mut sd,
+                                                                   ptr::null_mut())
+    })?;
+
+    // The socket file is a reparse point, which must be opened as such
+    let res = fs::OpenOptions::new()
+        .access_mode(netc::WRITE_DAC)
+        .custom_flags(netc::FILE_FLAG_OPEN_REPARSE_POINT | netc::FILE_FLAG_BACKUP_SEMANTICS)
+        .open(path)
+        .and_then(|file| {
+            sys::cvt(unsafe {
+                netc::SetKernelObjectSecurity(file.as_raw_handle() as netc::HANDLE,
+                                              netc::DACL_SECURITY_INFORMATION,
+                                              sd)
+            }).map(|_| ())
+        });
+    unsafe { netc::LocalFree(sd); }
+    res
+}
+
 /// An iterator over incoming connections to a [`UnixListener`].
 ///
 /// It will never return [`None`].
@@ -1028,591 +1859,812 @@ impl<'a> Iterator for Incoming<'a> {
 /// println!("socket {:?} sent {:?}", address, &buf[..count]);
 /// ```
 // #[stable(feature = "unix_socket", since = "1.10.0")]
-// Windows doesn't support SOCK_DGRAM yet
-// pub struct UnixDatagram(Socket);
-
-// #[stable(feature = "unix_socket", since = "1.10.0")]
-// impl fmt::Debug for UnixDatagram {
-//     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-//         let mut builder = fmt.debug_struct("UnixDatagram");
-//         builder.field("fd", self.0.as_inner());
-//         if let Ok(addr) = self.local_addr() {
-//             builder.field("local", &addr);
-//         }
-//         if let Ok(addr) = self.peer_addr() {
-//             builder.field("peer", &addr);
-//         }
-//         builder.finish()
-//     }
-// }
-
-// impl UnixDatagram {
-//     /// Creates a Unix datagram socket bound to the given path.
-//     ///
-//     /// # Examples
-//     ///
-//     /// ```ignore
-//     /// use std::os::windows::net::UnixDatagram;
-//     ///
-//     /// let sock = match UnixDatagram::bind("/path/to/the/socket") {
-//     ///     Ok(sock) => sock,
-//     ///     Err(e) => {
-//     ///         println!("Couldn't bind: {:?}", e);
-//     ///         return
-//     ///     }
-//     /// };
-//     /// ```
-//     // #[stable(feature = "unix_socket", since = "1.10.0")]
-//     pub fn bind<P: AsRef<Path>>(path: P) -> io::Result<UnixDatagram> {
-//         init();
-//         fn inner(path: &Path) -> io::Result<UnixDatagram> {
-//             unsafe {
-//                 let socket = UnixDatagram::unbound()?;
-//                 let (addr, len) = sockaddr_un(path)?;
-
-//                 cvt(bind(*socket.0.as_inner() as usize, &addr as *const _ as *const _, len as _))?;
-
-//                 Ok(socket)
-//             }
-//         }
-//         inner(path.as_ref())
-//     }
-
-//     /// Creates a Unix Datagram socket which is not bound to any address.
-//     ///
-//     /// # Examples
-//     ///
-//     /// ```ignore
-//     /// use std::os::windows::net::UnixDatagram;
-//     ///
-//     /// let sock = match UnixDatagram::unbound() {
-//     ///     Ok(sock) => sock,
-//     ///     Err(e) => {
-//     ///         println!("Couldn't unbound: {:?}", e);
-//     ///         return
-//     ///     }
-//     /// };
-//     /// ```
-//     // #[stable(feature = "unix_socket", since = "1.10.0")]
-//     pub fn unbound() -> io::Result<UnixDatagram> {
-//         init();
-//         let inner = Socket::new_raw(AF_UNIX, SOCK_DGRAM)?;
-//         Ok(UnixDatagram(inner))
-//     }
-
-//     /// Create an unnamed pair of connected sockets.
-//     ///
-//     /// Returns two `UnixDatagrams`s which are connected to each other.
-//     ///
-//     /// # Examples
-//     ///
-//     /// ```ignore
-//     /// use std::os::windows::net::UnixDatagram;
-//     ///
-//     /// let (sock1, sock2) = match UnixDatagram::pair() {
-//     ///     Ok((sock1, sock2)) => (sock1, sock2),
-//     ///     Err(e) => {
-//     ///         println!("Couldn't unbound: {:?}", e);
-//     ///         return
-//     ///     }
-//     /// };
-//     /// ```
-//     // #[stable(feature = "unix_socket", since = "1.10.0")]
-//     // Windows dosn't support socketpair()...this would need to be emulated
-//     // pub fn pair() -> io::Result<(UnixDatagram, UnixDatagram)> {
-//     //     init();
-//     //     let (i1, i2) = Socket::new_pair(AF_UNIX, SOCK_DGRAM)?;
-//     //     Ok((UnixDatagram(i1), UnixDatagram(i2)))
-//     // }
-
-//     /// Connects the socket to the specified address.
-//     ///
-//     /// The [`send`] method may be used to send data to the specified address.
-//     /// [`recv`] and [`recv_from`] will only receive data from that address.
-//     ///
-//     /// [`send`]: #method.send
-//     /// [`recv`]: #method.recv
-//     /// [`recv_from`]: #method.recv_from
-//     ///
-//     /// # Examples
-//     ///
-//     /// ```ignore
-//     /// use std::os::windows::net::UnixDatagram;
-//     ///
-//     /// let sock = UnixDatagram::unbound().unwrap();
-//     /// match sock.connect("/path/to/the/socket") {
-//     ///     Ok(sock) => sock,
-//     ///     Err(e) => {
-//     ///         println!("Couldn't connect: {:?}", e);
-//     ///         return
-//     ///     }
-//     /// };
-//     /// ```
-//     // #[stable(feature = "unix_socket", since = "1.10.0")]
-//     pub fn connect<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
-//         init();
-//         fn inner(d: &UnixDatagram, path: &Path) -> io::Result<()> {
-//             unsafe {
-//                 let (addr, len) = sockaddr_un(path)?;
-
-//                 cvt(connect(*d.0.as_inner() as usize, &addr as *const _ as *const _, len))?;
-
-//                 Ok(())
-//             }
-//         }
-//         inner(self, path.as_ref())
-//     }
-
-//     /// Creates a new independently owned handle to the underlying socket.
-//     ///
-//     /// The returned `UnixDatagram` is a reference to the same socket that this
-//     /// object references. Both handles can be used to accept incoming
-//     /// connections and options set on one side will affect the other.
-//     ///
-//     /// # Examples
-//     ///
-//     /// ```ignore
-//     /// use std::os::windows::net::UnixDatagram;
-//     ///
-//     /// let sock = UnixDatagram::bind("/path/to/the/socket").unwrap();
-//     ///
-//     /// let sock_copy = sock.try_clone().expect("try_clone failed");
-//     /// ```
-//     // #[stable(feature = "unix_socket", since = "1.10.0")]
-//     pub fn try_clone(&self) -> io::Result<UnixDatagram> {
-//         self.0.duplicate().map(UnixDatagram)
-//     }
-
-//     /// Returns the address of this socket.
-//     ///
-//     /// # Examples
-//     ///
-//     /// ```ignore
-//     /// use std::os::windows::net::UnixDatagram;
-//     ///
-//     /// let sock = UnixDatagram::bind("/path/to/the/socket").unwrap();
-//     ///
-//     /// let addr = sock.local_addr().expect("Couldn't get local address");
-//     /// ```
-//     // #[stable(feature = "unix_socket", since = "1.10.0")]
-//     pub fn local_addr(&self) -> io::Result<SocketAddr> {
-//         SocketAddr::new(|addr, len| unsafe { getsockname(*self.0.as_inner() as usize, addr, len) })
-//     }
-
-//     /// Returns the address of this socket's peer.
-//     ///
-//     /// The [`connect`] method will connect the socket to a peer.
-//     ///
-//     /// [`connect`]: #method.connect
-//     ///
-//     /// # Examples
-//     ///
-//     /// ```ignore
-//     /// use std::os::windows::net::UnixDatagram;
-//     ///
-//     /// let sock = UnixDatagram::unbound().unwrap();
-//     /// sock.connect("/path/to/the/socket").unwrap();
-//     ///
-//     /// let addr = sock.peer_addr().expect("Couldn't get peer address");
-//     /// ```
-//     // #[stable(feature = "unix_socket", since = "1.10.0")]
-//     pub fn peer_addr(&self) -> io::Result<SocketAddr> {
-//         SocketAddr::new(|addr, len| unsafe { getpeername(*self.0.as_inner() as usize, addr, len) })
-//     }
-
-//     /// Receives data from the socket.
-//     ///
-//     /// On success, returns the number of bytes read and the address from
-//     /// whence the data came.
-//     ///
-//     /// # Examples
-//     ///
-//     /// ```ignore
-//     /// use std::os::windows::net::UnixDatagram;
-//     ///
-//     /// let sock = UnixDatagram::unbound().unwrap();
-//     /// let mut buf = vec![0; 10];
-//     /// match sock.recv_from(buf.as_mut_slice()) {
-//     ///     Ok((size, sender)) => println!("received {} bytes from {:?}", size, sender),
-//     ///     Err(e) => println!("recv_from function failed: {:?}", e),
-//     /// }
-//     /// ```
-//     // #[stable(feature = "unix_socket", since = "1.10.0")]
-//     pub fn recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
-//         let mut count = 0;
-//         let addr = SocketAddr::new(|addr, len| {
-//             unsafe {
-//                 count = recvfrom(*self.0.as_inner() as usize,
-//                                        buf.as_mut_ptr() as *mut _,
-//                                        buf.len() as i32,
-//                                        0,
-//                                        addr,
-//                                        len);
-//                 if count > 0 {
-//                     1
-//                 } else if count == 0 {
-//                     0
-//                 } else {
-//                     -1
-//                 }
-//             }
-//         })?;
-
-//         Ok((count as usize, addr))
-//     }
-
-//     /// Receives data from the socket.
-//     ///
-//     /// On success, returns the number of bytes read.
-//     ///
-//     /// # Examples
-//     ///
-//     /// ```ignore
-//     /// use std::os::windows::net::UnixDatagram;
-//     ///
-//     /// let sock = UnixDatagram::bind("/path/to/the/socket").unwrap();
-//     /// let mut buf = vec![0; 10];
-//     /// sock.recv(buf.as_mut_slice()).expect("recv function failed");
-//     /// ```
-//     // #[stable(feature = "unix_socket", since = "1.10.0")]
-//     pub fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
-//         self.0.read(buf)
-//     }
-
-//     /// Sends data on the socket to the specified address.
-//     ///
-//     /// On success, returns the number of bytes written.
-//     ///
-//     /// # Examples
-//     ///
-//     /// ```ignore
-//     /// use std::os::windows::net::UnixDatagram;
-//     ///
-//     /// let sock = UnixDatagram::unbound().unwrap();
-//     /// sock.send_to(b"omelette au fromage", "/some/sock").expect("send_to function failed");
-//     /// ```
-//     // #[stable(feature = "unix_socket", since = "1.10.0")]
-//     pub fn send_to<P: AsRef<Path>>(&self, buf: &[u8], path: P) -> io::Result<usize> {
-//         fn inner(d: &UnixDatagram, buf: &[u8], path: &Path) -> io::Result<usize> {
-//             unsafe {
-//                 let (addr, len) = sockaddr_un(path)?;
-
-//                 let count = cvt(sendto(*d.0.as_inner() as usize,
-//                                              buf.as_ptr() as *const _,
-//                                              buf.len() as i32,
-//                                              MSG_NOSIGNAL,
-//                                              &addr as *const _ as *const _,
-//                                              len))?;
-//                 Ok(count as usize)
-//             }
-//         }
-//         inner(self, buf, path.as_ref())
-//     }
-
-//     /// Sends data on the socket to the socket's peer.
-//     ///
-//     /// The peer address may be set by the `connect` method, and this method
-//     /// will return an error if the socket has not already been connected.
-//     ///
-//     /// On success, returns the number of bytes written.
-//     ///
-//     /// # Examples
-//     ///
-//     /// ```ignore
-//     /// use std::os::windows::net::UnixDatagram;
-//     ///
-//     /// let sock = UnixDatagram::unbound().unwrap();
-//     /// sock.connect("/some/sock").expect("Couldn't connect");
-//     /// sock.send(b"omelette au fromage").expect("send_to function failed");
-//     /// ```
-//     // #[stable(feature = "unix_socket", since = "1.10.0")]
-//     pub fn send(&self, buf: &[u8]) -> io::Result<usize> {
-//         let len = cmp::min(buf.len(), <wrlen_t>::max_value() as usize) as wrlen_t;
-//         let ret = cvt(unsafe {
-//             netc::send(*self.0.as_inner(),
-//                        buf.as_ptr() as *const libc::c_void,
-//                        len,
-//                        MSG_NOSIGNAL)
-//         })?;
-//         Ok(ret as usize)
-//     }
-
-//     /// Sets the read timeout for the socket.
-//     ///
-//     /// If the provided value is [`None`], then [`recv`] and [`recv_from`] calls will
-//     /// block indefinitely. An [`Err`] is returned if the zero [`Duration`]
-//     /// is passed to this method.
-//     ///
-//     /// [`None`]: ../../../../std/option/enum.Option.html#variant.None
-//     /// [`Err`]: ../../../../std/result/enum.Result.html#variant.Err
-//     /// [`recv`]: #method.recv
-//     /// [`recv_from`]: #method.recv_from
-//     /// [`Duration`]: ../../../../std/time/struct.Duration.html
-//     ///
-//     /// # Examples
-//     ///
-//     /// ```ignore
-//     /// use std::os::windows::net::UnixDatagram;
-//     /// use std::time::Duration;
-//     ///
-//     /// let sock = UnixDatagram::unbound().unwrap();
-//     /// sock.set_read_timeout(Some(Duration::new(1, 0))).expect("set_read_timeout function failed");
-//     /// ```
-//     ///
-//     /// An [`Err`] is returned if the zero [`Duration`] is passed to this
-//     /// method:
-//     ///
-//     /// ```ignore
-//     /// use std::io;
-//     /// use std::os::windows::net::UnixDatagram;
-//     /// use std::time::Duration;
-//     ///
-//     /// let socket = UnixDatagram::unbound().unwrap();
-//     /// let result = socket.set_read_timeout(Some(Duration::new(0, 0)));
-//     /// let err = result.unwrap_err();
-//     /// assert_eq!(err.kind(), io::ErrorKind::InvalidInput)
-//     /// ```
-//     // #[stable(feature = "unix_socket", since = "1.10.0")]
-//     pub fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
-//         self.0.set_timeout(timeout, SO_RCVTIMEO)
-//     }
-
-//     /// Sets the write timeout for the socket.
-//     ///
-//     /// If the provided value is [`None`], then [`send`] and [`send_to`] calls will
-//     /// block indefinitely. An [`Err`] is returned if the zero [`Duration`] is passed to this
-//     /// method.
-//     ///
-//     /// [`None`]: ../../../../std/option/enum.Option.html#variant.None
-//     /// [`send`]: #method.send
-//     /// [`send_to`]: #method.send_to
-//     /// [`Duration`]: ../../../../std/time/struct.Duration.html
-//     ///
-//     /// # Examples
-//     ///
-//     /// ```ignore
-//     /// use std::os::windows::net::UnixDatagram;
-//     /// use std::time::Duration;
-//     ///
-//     /// let sock = UnixDatagram::unbound().unwrap();
-//     /// sock.set_write_timeout(Some(Duration::new(1, 0)))
-//     ///     .expect("set_write_timeout function failed");
-//     /// ```
-//     ///
-//     /// An [`Err`] is returned if the zero [`Duration`] is passed to this
-//     /// method:
-//     ///
-//     /// ```ignore
-//     /// use std::io;
-//     /// use std::os::windows::net::UnixDatagram;
-//     /// use std::time::Duration;
-//     ///
-//     /// let socket = UnixDatagram::unbound().unwrap();
-//     /// let result = socket.set_write_timeout(Some(Duration::new(0, 0)));
-//     /// let err = result.unwrap_err();
-//     /// assert_eq!(err.kind(), io::ErrorKind::InvalidInput)
-//     /// ```
-//     // #[stable(feature = "unix_socket", since = "1.10.0")]
-//     pub fn set_write_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
-//         self.0.set_timeout(timeout, SO_SNDTIMEO)
-//     }
-
-//     /// Returns the read timeout of this socket.
-//     ///
-//     /// # Examples
-//     ///
-//     /// ```ignore
-//     /// use std::os::windows::net::UnixDatagram;
-//     /// use std::time::Duration;
-//     ///
-//     /// let sock = UnixDatagram::unbound().unwrap();
-//     /// sock.set_read_timeout(Some(Duration::new(1, 0))).expect("set_read_timeout function failed");
-//     /// assert_eq!(sock.read_timeout().unwrap(), Some(Duration::new(1, 0)));
-//     /// ```
-//     // #[stable(feature = "unix_socket", since = "1.10.0")]
-//     pub fn read_timeout(&self) -> io::Result<Option<Duration>> {
-//         self.0.timeout(SO_RCVTIMEO)
-//     }
-
-//     /// Returns the write timeout of this socket.
-//     ///
-//     /// # Examples
-//     ///
-//     /// ```ignore
-//     /// use std::os::windows::net::UnixDatagram;
-//     /// use std::time::Duration;
-//     ///
-//     /// let sock = UnixDatagram::unbound().unwrap();
-//     /// sock.set_write_timeout(Some(Duration::new(1, 0)))
-//     ///     .expect("set_write_timeout function failed");
-//     /// assert_eq!(sock.write_timeout().unwrap(), Some(Duration::new(1, 0)));
-//     /// ```
-//     // #[stable(feature = "unix_socket", since = "1.10.0")]
-//     pub fn write_timeout(&self) -> io::Result<Option<Duration>> {
-//         self.0.timeout(SO_SNDTIMEO)
-//     }
-
-//     /// Moves the socket into or out of nonblocking mode.
-//     ///
-//     /// # Examples
-//     ///
-//     /// ```ignore
-//     /// use std::os::windows::net::UnixDatagram;
-//     ///
-//     /// let sock = UnixDatagram::unbound().unwrap();
-//     /// sock.set_nonblocking(true).expect("set_nonblocking function failed");
-//     /// ```
-//     // #[stable(feature = "unix_socket", since = "1.10.0")]
-//     pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
-//         self.0.set_nonblocking(nonblocking)
-//     }
-
-//     /// Returns the value of the `SO_ERROR` option.
-//     ///
-//     /// # Examples
-//     ///
-//     /// ```ignore
-//     /// use std::os::windows::net::UnixDatagram;
-//     ///
-//     /// let sock = UnixDatagram::unbound().unwrap();
-//     /// if let Ok(Some(err)) = sock.take_error() {
-//     ///     println!("Got error: {:?}", err);
-//     /// }
-//     /// ```
-//     // #[stable(feature = "unix_socket", since = "1.10.0")]
-//     pub fn take_error(&self) -> io::Result<Option<io::Error>> {
-//         self.0.take_error()
-//     }
-
-//     /// Shut down the read, write, or both halves of this connection.
-//     ///
-//     /// This function will cause all pending and future I/O calls on the
-//     /// specified portions to immediately return with an appropriate value
-//     /// (see the documentation of [`Shutdown`]).
-//     ///
-//     /// [`Shutdown`]: ../../../../std/net/enum.Shutdown.html
-//     ///
-//     /// ```ignore
-//     /// use std::os::windows::net::UnixDatagram;
-//     /// use std::net::Shutdown;
-//     ///
-//     /// let sock = UnixDatagram::unbound().unwrap();
-//     /// sock.shutdown(Shutdown::Both).expect("shutdown function failed");
-//     /// ```
-//     // #[stable(feature = "unix_socket", since = "1.10.0")]
-//     pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
-//         self.0.shutdown(how)
-//     }
-// }
-
-// // #[stable(feature = "unix_socket", since = "1.10.0")]
-// impl AsRawFd for UnixDatagram {
-//     fn as_raw_fd(&self) -> RawFd {
-//         *self.0.as_inner()
-//     }
-// }
-
-// // #[stable(feature = "unix_socket", since = "1.10.0")]
-// impl FromRawFd for UnixDatagram {
-//     unsafe fn from_raw_fd(fd: RawFd) -> UnixDatagram {
-//         UnixDatagram(Socket::from_inner(fd))
-//     }
-// }
+pub struct UnixDatagram {
+    inner: Socket,
+    state: Arc<DatagramState>,
+}
 
-// // #[stable(feature = "unix_socket", since = "1.10.0")]
-// impl IntoRawFd for UnixDatagram {
-//     fn into_raw_fd(self) -> RawFd {
-//         self.0.into_inner()
-//     }
-// }
+// Winsock only implements SOCK_STREAM for AF_UNIX, so datagram sockets are
+// emulated on top of stream sockets. A bound `UnixDatagram` is a listening
+// socket, and every datagram is delivered over its own connection to that
+// listener as a single frame:
+//
+//     u16 (LE)  length of the sender's `sun_path`, 0 if it is unbound
+//     [u8]      the sender's `sun_path`
+//     u32 (LE)  length of the payload
+//     [u8]      the payload
+//
+// A frame that is cut short because the sender went away, or stalls for
+// longer than `FRAME_TIMEOUT_MS`, is dropped rather than delivered as a partial
+// datagram. Datagrams waiting to be received are connections waiting to be
+// accepted, so a receiver whose listen backlog is full refuses new ones.
+//
+// Whatever a real datagram socket would keep in the kernel lives in
+// `DatagramState`, which is shared with every handle made by `try_clone`.
+struct DatagramState {
+    local: SocketAddr,
+    peer: Mutex<Option<SocketAddr>>,
+    nonblocking: AtomicBool,
+    read_shutdown: AtomicBool,
+    write_shutdown: AtomicBool,
+    // Socket file created by `UnixDatagram::pair`, removed with the last handle.
+    temp_path: Option<PathBuf>,
+}
 
-#[cfg(all(test, not(target_os = "emscripten")))]
-mod test {
-    use std::thread;
-    use std::io::{self, ErrorKind};
-    use std::io::prelude::*;
-    use std::time::Duration;
-    use sys_common::io::test::tmpdir;
+// How many datagrams a bound `UnixDatagram` can have queued by default.
+const DATAGRAM_BACKLOG: libc::c_int = 128;
 
-    use super::*;
+// How long, in milliseconds, a sender may stall in the middle of a frame
+// before it is dropped.
+const FRAME_TIMEOUT_MS: u64 = 100;
 
-    macro_rules! or_panic {
-        ($e:expr) => {
-            match $e {
-                Ok(e) => e,
-                Err(e) => panic!("{}", e),
-            }
+impl Drop for DatagramState {
+    fn drop(&mut self) {
+        if let Some(ref path) = self.temp_path {
+            let _ = fs::remove_file(path);
         }
     }
+}
 
-    #[test]
-    fn basic() {
-        let dir = tmpdir();
-        let socket_path = dir.path().join("sock");
-        let msg1 = b"hello";
-        let msg2 = b"world!";
-
-        let listener = or_panic!(UnixListener::bind(&socket_path));
-        let thread = thread::spawn(move || {
-            let mut stream = or_panic!(listener.accept()).0;
-            let mut buf = [0; 5];
-            or_panic!(stream.read(&mut buf));
-            assert_eq!(&msg1[..], &buf[..]);
-            or_panic!(stream.write_all(msg2));
-        });
+// Returns a path in the temporary directory which no other socket created by
+// this process has used.
+fn temp_socket_path() -> PathBuf {
+    static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
 
-        let mut stream = or_panic!(UnixStream::connect(&socket_path));
-        assert_eq!(Some(&*socket_path),
-                   stream.peer_addr().unwrap().as_pathname());
-        or_panic!(stream.write_all(msg1));
-        let mut buf = vec![];
-        or_panic!(stream.read_to_end(&mut buf));
-        assert_eq!(&msg2[..], &buf[..]);
-        drop(stream);
+    let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+    env::temp_dir().join(format!("rust-sock-{}-{}", process::id(), id))
+}
 
-        thread.join().unwrap();
+// Binds a socket to a fresh temporary path, skipping over socket files left
+// behind by an earlier process which had the same id.
+fn bind_temp<T, F>(mut bind: F) -> io::Result<(T, PathBuf)>
+    where F: FnMut(&Path) -> io::Result<T>
+{
+    loop {
+        let path = temp_socket_path();
+        match bind(&path) {
+            Ok(socket) => return Ok((socket, path)),
+            Err(ref e) if e.kind() == io::ErrorKind::AddrInUse => {}
+            Err(e) => return Err(e),
+        }
     }
+}
 
-    // #[test]
-    // fn pair() {
-    //     let msg1 = b"hello";
-    //     let msg2 = b"world!";
+fn write_frame(stream: &mut UnixStream, from: &SocketAddr, buf: &[u8]) -> io::Result<()> {
+    if buf.len() > u32::max_value() as usize {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                  "datagram is too large"));
+    }
 
-    //     let (mut s1, mut s2) = or_panic!(UnixStream::pair());
-    //     let thread = thread::spawn(move || {
-    //         // s1 must be moved in or the test will hang!
-    //         let mut buf = [0; 5];
-    //         or_panic!(s1.read(&mut buf));
-    //         assert_eq!(&msg1[..], &buf[..]);
-    //         or_panic!(s1.write_all(msg2));
-    //     });
+    let from = from.sun_path();
+    let len = buf.len() as u32;
+    let mut header = Vec::with_capacity(2 + from.len() + 4);
+    header.push(from.len() as u8);
+    header.push((from.len() >> 8) as u8);
+    header.extend_from_slice(from);
+    header.extend_from_slice(&[len as u8, (len >> 8) as u8, (len >> 16) as u8, (len >> 24) as u8]);
 
-    //     or_panic!(s2.write_all(msg1));
-    //     let mut buf = vec![];
-    //     or_panic!(s2.read_to_end(&mut buf));
-    //     assert_eq!(&msg2[..], &buf[..]);
-    //     drop(s2);
+    stream.write_all(&header)?;
+    stream.write_all(buf)
+}
 
-    //     thread.join().unwrap();
-    // }
+fn read_frame(stream: &mut UnixStream, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
+    let mut addr: netc::sockaddr_un = unsafe { mem::zeroed() };
+    addr.sun_family = AF_UNIX as netc::sa_family_t;
 
-    #[test]
-    fn try_clone() {
-        let dir = tmpdir();
-        let socket_path = dir.path().join("sock");
-        let msg1 = b"hello";
-        let msg2 = b"world";
+    let mut header = [0u8; 2];
+    stream.read_exact(&mut header)?;
+    let addr_len = header[0] as usize | (header[1] as usize) << 8;
+    if addr_len > addr.sun_path.len() {
+        return Err(io::Error::new(io::ErrorKind::InvalidData,
+                                  "malformed datagram frame"));
+    }
+    stream.read_exact(unsafe {
+        mem::transmute::<&mut [libc::c_char], &mut [u8]>(&mut addr.sun_path[..addr_len])
+    })?;
+
+    let mut header = [0u8; 4];
+    stream.read_exact(&mut header)?;
+    let len = header[0] as usize | (header[1] as usize) << 8 |
+              (header[2] as usize) << 16 | (header[3] as usize) << 24;
+
+    // Like the kernel does, silently truncate datagrams which don't fit in `buf`
+    let count = cmp::min(len, buf.len());
+    stream.read_exact(&mut buf[..count])?;
+    let rest = (len - count) as u64;
+    if io::copy(&mut stream.take(rest), &mut io::sink())? != rest {
+        return Err(io::Error::new(io::ErrorKind::UnexpectedEof,
+                                  "datagram frame was cut short"));
+    }
 
-        let listener = or_panic!(UnixListener::bind(&socket_path));
-        let thread = thread::spawn(move || {
-            #[allow(unused_mut)]
-            let mut stream = or_panic!(listener.accept()).0;
-            or_panic!(stream.write_all(msg1));
-            or_panic!(stream.write_all(msg2));
-        });
+    let addr = SocketAddr::from_parts(addr, (sun_path_offset() + addr_len) as libc::c_int)?;
+    Ok((count, addr))
+}
 
-        let mut stream = or_panic!(UnixStream::connect(&socket_path));
-        let mut stream2 = or_panic!(stream.try_clone());
+// #[stable(feature = "unix_socket", since = "1.10.0")]
+impl fmt::Debug for UnixDatagram {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let mut builder = fmt.debug_struct("UnixDatagram");
+        builder.field("fd", self.inner.as_inner());
+        if let Ok(addr) = self.local_addr() {
+            builder.field("local", &addr);
+        }
+        if let Ok(addr) = self.peer_addr() {
+            builder.field("peer", &addr);
+        }
+        builder.finish()
+    }
+}
+
+impl UnixDatagram {
+    /// Creates a Unix datagram socket bound to the given path.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::os::windows::net::UnixDatagram;
+    ///
+    /// let sock = match UnixDatagram::bind("/path/to/the/socket") {
+    ///     Ok(sock) => sock,
+    ///     Err(e) => {
+    ///         println!("Couldn't bind: {:?}", e);
+    ///         return
+    ///     }
+    /// };
+    /// ```
+    // #[stable(feature = "unix_socket", since = "1.10.0")]
+    pub fn bind<P: AsRef<Path>>(path: P) -> io::Result<UnixDatagram> {
+        UnixDatagram::bind_backlog(path, DATAGRAM_BACKLOG)
+    }
+
+    /// Creates a Unix datagram socket bound to the given path, which can have
+    /// up to `backlog` datagrams waiting to be received.
+    ///
+    /// Datagram sockets are emulated on top of stream sockets on Windows, and
+    /// each datagram waiting to be received takes up a slot in the listen
+    /// backlog of the underlying socket. Once the backlog is full, senders
+    /// fail with [`ConnectionRefused`] until a datagram is received. [`bind`]
+    /// uses a backlog of 128.
+    ///
+    /// [`ConnectionRefused`]: ../../../../std/io/enum.ErrorKind.html#variant.ConnectionRefused
+    /// [`bind`]: #method.bind
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::os::windows::net::UnixDatagram;
+    ///
+    /// let sock = UnixDatagram::bind_backlog("/path/to/the/socket", 1024).unwrap();
+    /// ```
+    pub fn bind_backlog<P: AsRef<Path>>(path: P, backlog: i32) -> io::Result<UnixDatagram> {
+        init();
+        let inner = UnixDatagram::bind_socket(path.as_ref(), backlog)?;
+        UnixDatagram::from_socket(inner, None)
+    }
+
+    fn bind_socket(path: &Path, backlog: libc::c_int) -> io::Result<Socket> {
+        unsafe {
+            let inner = Socket::new_raw(AF_UNIX, SOCK_STREAM)?;
+            let (addr, len) = sockaddr_un(path)?;
+
+            cvt(bind(*inner.as_inner() as usize, &addr as *const _ as *const _, len as _))?;
+            cvt(listen(*inner.as_inner() as usize, backlog))?;
+
+            Ok(inner)
+        }
+    }
+
+    fn from_socket(inner: Socket, temp_path: Option<PathBuf>) -> io::Result<UnixDatagram> {
+        // The name is looked up once, rather than on every send.
+        let local = SocketAddr::new(|addr, len| unsafe {
+            getsockname(*inner.as_inner() as usize, addr, len)
+        });
+        let local = match local {
+            // Winsock refuses to name a socket that was never bound
+            Err(ref e) if e.kind() == io::ErrorKind::InvalidInput => SocketAddr::unnamed(),
+            local => local?,
+        };
+        Ok(UnixDatagram {
+            inner,
+            state: Arc::new(DatagramState {
+                local,
+                peer: Mutex::new(None),
+                nonblocking: AtomicBool::new(false),
+                read_shutdown: AtomicBool::new(false),
+                write_shutdown: AtomicBool::new(false),
+                temp_path,
+            }),
+        })
+    }
+
+    /// Creates a Unix Datagram socket which is not bound to any address.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::os::windows::net::UnixDatagram;
+    ///
+    /// let sock = match UnixDatagram::unbound() {
+    ///     Ok(sock) => sock,
+    ///     Err(e) => {
+    ///         println!("Couldn't unbound: {:?}", e);
+    ///         return
+    ///     }
+    /// };
+    /// ```
+    // #[stable(feature = "unix_socket", since = "1.10.0")]
+    pub fn unbound() -> io::Result<UnixDatagram> {
+        init();
+        let inner = Socket::new_raw(AF_UNIX, SOCK_STREAM)?;
+        UnixDatagram::from_socket(inner, None)
+    }
+
+    /// Create an unnamed pair of connected sockets.
+    ///
+    /// Returns two `UnixDatagrams`s which are connected to each other.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::os::windows::net::UnixDatagram;
+    ///
+    /// let (sock1, sock2) = match UnixDatagram::pair() {
+    ///     Ok((sock1, sock2)) => (sock1, sock2),
+    ///     Err(e) => {
+    ///         println!("Couldn't unbound: {:?}", e);
+    ///         return
+    ///     }
+    /// };
+    /// ```
+    // #[stable(feature = "unix_socket", since = "1.10.0")]
+    pub fn pair() -> io::Result<(UnixDatagram, UnixDatagram)> {
+        init();
+        // Without socketpair() both ends need a name to be reachable, so
+        // each one is bound to a temporary path that goes away with it.
+        let bind = |path: &Path| UnixDatagram::bind_socket(path, DATAGRAM_BACKLOG);
+        let (i1, path1) = bind_temp(bind)?;
+        let (i2, path2) = bind_temp(bind)?;
+        let s1 = UnixDatagram::from_socket(i1, Some(path1.clone()))?;
+        let s2 = UnixDatagram::from_socket(i2, Some(path2.clone()))?;
+        s1.connect(&path2)?;
+        s2.connect(&path1)?;
+        Ok((s1, s2))
+    }
+
+    /// Connects the socket to the specified address.
+    ///
+    /// The [`send`] method may be used to send data to the specified address.
+    /// [`recv`] and [`recv_from`] will only receive data from that address.
+    ///
+    /// [`send`]: #method.send
+    /// [`recv`]: #method.recv
+    /// [`recv_from`]: #method.recv_from
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::os::windows::net::UnixDatagram;
+    ///
+    /// let sock = UnixDatagram::unbound().unwrap();
+    /// match sock.connect("/path/to/the/socket") {
+    ///     Ok(sock) => sock,
+    ///     Err(e) => {
+    ///         println!("Couldn't connect: {:?}", e);
+    ///         return
+    ///     }
+    /// };
+    /// ```
+    // #[stable(feature = "unix_socket", since = "1.10.0")]
+    pub fn connect<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        init();
+        fn inner(d: &UnixDatagram, path: &Path) -> io::Result<()> {
+            let (addr, len) = unsafe { sockaddr_un(path)? };
+            let peer = SocketAddr::from_parts(addr, len)?;
+
+            *d.state.peer.lock().unwrap() = Some(peer);
+            Ok(())
+        }
+        inner(self, path.as_ref())
+    }
+
+    /// Creates a new independently owned handle to the underlying socket.
+    ///
+    /// The returned `UnixDatagram` is a reference to the same socket that this
+    /// object references. Both handles can be used to accept incoming
+    /// connections and options set on one side will affect the other.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::os::windows::net::UnixDatagram;
+    ///
+    /// let sock = UnixDatagram::bind("/path/to/the/socket").unwrap();
+    ///
+    /// let sock_copy = sock.try_clone().expect("try_clone failed");
+    /// ```
+    // #[stable(feature = "unix_socket", since = "1.10.0")]
+    pub fn try_clone(&self) -> io::Result<UnixDatagram> {
+        self.inner.duplicate().map(|inner| UnixDatagram {
+            inner,
+            state: self.state.clone(),
+        })
+    }
+
+    /// Returns the address of this socket.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::os::windows::net::UnixDatagram;
+    ///
+    /// let sock = UnixDatagram::bind("/path/to/the/socket").unwrap();
+    ///
+    /// let addr = sock.local_addr().expect("Couldn't get local address");
+    /// ```
+    // #[stable(feature = "unix_socket", since = "1.10.0")]
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        Ok(self.state.local.clone())
+    }
+
+    /// Returns the address of this socket's peer.
+    ///
+    /// The [`connect`] method will connect the socket to a peer.
+    ///
+    /// [`connect`]: #method.connect
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::os::windows::net::UnixDatagram;
+    ///
+    /// let sock = UnixDatagram::unbound().unwrap();
+    /// sock.connect("/path/to/the/socket").unwrap();
+    ///
+    /// let addr = sock.peer_addr().expect("Couldn't get peer address");
+    /// ```
+    // #[stable(feature = "unix_socket", since = "1.10.0")]
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.state.peer.lock().unwrap().clone().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::NotConnected, "socket is not connected")
+        })
+    }
+
+    /// Receives data from the socket.
+    ///
+    /// On success, returns the number of bytes read and the address from
+    /// whence the data came.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::os::windows::net::UnixDatagram;
+    ///
+    /// let sock = UnixDatagram::unbound().unwrap();
+    /// let mut buf = vec![0; 10];
+    /// match sock.recv_from(buf.as_mut_slice()) {
+    ///     Ok((size, sender)) => println!("received {} bytes from {:?}", size, sender),
+    ///     Err(e) => println!("recv_from function failed: {:?}", e),
+    /// }
+    /// ```
+    // #[stable(feature = "unix_socket", since = "1.10.0")]
+    pub fn recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
+        loop {
+            if self.state.read_shutdown.load(Ordering::SeqCst) {
+                return Ok((0, SocketAddr::unnamed()));
+            }
+
+            let (count, addr) = match self.recv_frame(buf)? {
+                Some(frame) => frame,
+                None => continue,
+            };
+
+            // A connected socket only hears from its peer
+            match *self.state.peer.lock().unwrap() {
+                Some(ref peer) if !peer.names_same(&addr) => continue,
+                _ => return Ok((count, addr)),
+            }
+        }
+    }
+
+    fn recv_frame(&self, buf: &mut [u8]) -> io::Result<Option<(usize, SocketAddr)>> {
+        let timeout = self.inner.timeout(SO_RCVTIMEO)?;
+        if !self.state.nonblocking.load(Ordering::SeqCst) {
+            if let Some(dur) = timeout {
+                self.inner.wait_readable(dur)?;
+            }
+        }
+
+        let sock = self.inner.accept(ptr::null_mut(), ptr::null_mut())?;
+        // Accepted sockets inherit the listener's non-blocking mode, but once
+        // a sender has connected its frame is read as a whole. A sender that
+        // stalls part way through would hold up the receiver indefinitely, so
+        // each read is bounded and the sender dropped if it runs out.
+        sock.set_nonblocking(false)?;
+        let frame_timeout = Duration::from_millis(FRAME_TIMEOUT_MS);
+        let frame_timeout = match timeout {
+            Some(dur) => cmp::min(dur, frame_timeout),
+            None => frame_timeout,
+        };
+        sock.set_timeout(Some(frame_timeout), SO_RCVTIMEO)?;
+
+        match read_frame(&mut UnixStream(sock), buf) {
+            Err(ref e) if e.kind() == io::ErrorKind::UnexpectedEof ||
+                          e.kind() == io::ErrorKind::TimedOut ||
+                          e.kind() == io::ErrorKind::WouldBlock => Ok(None),
+            frame => frame.map(Some),
+        }
+    }
+
+    /// Receives data from the socket.
+    ///
+    /// On success, returns the number of bytes read.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::os::windows::net::UnixDatagram;
+    ///
+    /// let sock = UnixDatagram::bind("/path/to/the/socket").unwrap();
+    /// let mut buf = vec![0; 10];
+    /// sock.recv(buf.as_mut_slice()).expect("recv function failed");
+    /// ```
+    // #[stable(feature = "unix_socket", since = "1.10.0")]
+    pub fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.recv_from(buf).map(|(count, _)| count)
+    }
+
+    /// Sends data on the socket to the specified address.
+    ///
+    /// On success, returns the number of bytes written.
+    ///
+    /// If the receiver already has as many datagrams waiting as its backlog
+    /// allows (see [`bind_backlog`]), this fails with [`ConnectionRefused`],
+    /// the same error as for a socket file that nothing is bound to.
+    ///
+    /// [`bind_backlog`]: #method.bind_backlog
+    /// [`ConnectionRefused`]: ../../../../std/io/enum.ErrorKind.html#variant.ConnectionRefused
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::os::windows::net::UnixDatagram;
+    ///
+    /// let sock = UnixDatagram::unbound().unwrap();
+    /// sock.send_to(b"omelette au fromage", "/some/sock").expect("send_to function failed");
+    /// ```
+    // #[stable(feature = "unix_socket", since = "1.10.0")]
+    pub fn send_to<P: AsRef<Path>>(&self, buf: &[u8], path: P) -> io::Result<usize> {
+        fn inner(d: &UnixDatagram, buf: &[u8], path: &Path) -> io::Result<usize> {
+            let (addr, len) = unsafe { sockaddr_un(path)? };
+            d.send_frame(&addr, len, buf)
+        }
+        inner(self, buf, path.as_ref())
+    }
+
+    /// Sends data on the socket to the socket's peer.
+    ///
+    /// The peer address may be set by the `connect` method, and this method
+    /// will return an error if the socket has not already been connected.
+    ///
+    /// On success, returns the number of bytes written.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::os::windows::net::UnixDatagram;
+    ///
+    /// let sock = UnixDatagram::unbound().unwrap();
+    /// sock.connect("/some/sock").expect("Couldn't connect");
+    /// sock.send(b"omelette au fromage").expect("send_to function failed");
+    /// ```
+    // #[stable(feature = "unix_socket", since = "1.10.0")]
+    pub fn send(&self, buf: &[u8]) -> io::Result<usize> {
+        let peer = self.peer_addr()?;
+        self.send_frame(&peer.addr, peer.len, buf)
+    }
+
+    fn send_frame(&self, addr: &netc::sockaddr_un, len: libc::c_int,
+                  buf: &[u8]) -> io::Result<usize> {
+        if self.state.write_shutdown.load(Ordering::SeqCst) {
+            return Err(io::Error::new(io::ErrorKind::BrokenPipe,
+                                      "cannot send after shutdown"));
+        }
+
+        let sock = Socket::new_raw(AF_UNIX, SOCK_STREAM)?;
+        sock.set_timeout(self.inner.timeout(SO_SNDTIMEO)?, SO_SNDTIMEO)?;
+        cvt(unsafe { connect(*sock.as_inner() as usize, addr as *const _ as *const _, len) })?;
+
+        write_frame(&mut UnixStream(sock), &self.state.local, buf)?;
+        Ok(buf.len())
+    }
+
+    /// Sets the read timeout for the socket.
+    ///
+    /// If the provided value is [`None`], then [`recv`] and [`recv_from`] calls will
+    /// block indefinitely. An [`Err`] is returned if the zero [`Duration`]
+    /// is passed to this method.
+    ///
+    /// [`None`]: ../../../../std/option/enum.Option.html#variant.None
+    /// [`Err`]: ../../../../std/result/enum.Result.html#variant.Err
+    /// [`recv`]: #method.recv
+    /// [`recv_from`]: #method.recv_from
+    /// [`Duration`]: ../../../../std/time/struct.Duration.html
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::os::windows::net::UnixDatagram;
+    /// use std::time::Duration;
+    ///
+    /// let sock = UnixDatagram::unbound().unwrap();
+    /// sock.set_read_timeout(Some(Duration::new(1, 0))).expect("set_read_timeout function failed");
+    /// ```
+    ///
+    /// An [`Err`] is returned if the zero [`Duration`] is passed to this
+    /// method:
+    ///
+    /// ```ignore
+    /// use std::io;
+    /// use std::os::windows::net::UnixDatagram;
+    /// use std::time::Duration;
+    ///
+    /// let socket = UnixDatagram::unbound().unwrap();
+    /// let result = socket.set_read_timeout(Some(Duration::new(0, 0)));
+    /// let err = result.unwrap_err();
+    /// assert_eq!(err.kind(), io::ErrorKind::InvalidInput)
+    /// ```
+    // #[stable(feature = "unix_socket", since = "1.10.0")]
+    pub fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.inner.set_timeout(timeout, SO_RCVTIMEO)
+    }
+
+    /// Sets the write timeout for the socket.
+    ///
+    /// If the provided value is [`None`], then [`send`] and [`send_to`] calls will
+    /// block indefinitely. An [`Err`] is returned if the zero [`Duration`] is passed to this
+    /// method.
+    ///
+    /// [`None`]: ../../../../std/option/enum.Option.html#variant.None
+    /// [`send`]: #method.send
+    /// [`send_to`]: #method.send_to
+    /// [`Duration`]: ../../../../std/time/struct.Duration.html
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::os::windows::net::UnixDatagram;
+    /// use std::time::Duration;
+    ///
+    /// let sock = UnixDatagram::unbound().unwrap();
+    /// sock.set_write_timeout(Some(Duration::new(1, 0)))
+    ///     .expect("set_write_timeout function failed");
+    /// ```
+    ///
+    /// An [`Err`] is returned if the zero [`Duration`] is passed to this
+    /// method:
+    ///
+    /// ```ignore
+    /// use std::io;
+    /// use std::os::windows::net::UnixDatagram;
+    /// use std::time::Duration;
+    ///
+    /// let socket = UnixDatagram::unbound().unwrap();
+    /// let result = socket.set_write_timeout(Some(Duration::new(0, 0)));
+    /// let err = result.unwrap_err();
+    /// assert_eq!(err.kind(), io::ErrorKind::InvalidInput)
+    /// ```
+    // #[stable(feature = "unix_socket", since = "1.10.0")]
+    pub fn set_write_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.inner.set_timeout(timeout, SO_SNDTIMEO)
+    }
+
+    /// Returns the read timeout of this socket.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::os::windows::net::UnixDatagram;
+    /// use std::time::Duration;
+    ///
+    /// let sock = UnixDatagram::unbound().unwrap();
+    /// sock.set_read_timeout(Some(Duration::new(1, 0))).expect("set_read_timeout function failed");
+    /// assert_eq!(sock.read_timeout().unwrap(), Some(Duration::new(1, 0)));
+    /// ```
+    // #[stable(feature = "unix_socket", since = "1.10.0")]
+    pub fn read_timeout(&self) -> io::Result<Option<Duration>> {
+        self.inner.timeout(SO_RCVTIMEO)
+    }
+
+    /// Returns the write timeout of this socket.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::os::windows::net::UnixDatagram;
+    /// use std::time::Duration;
+    ///
+    /// let sock = UnixDatagram::unbound().unwrap();
+    /// sock.set_write_timeout(Some(Duration::new(1, 0)))
+    ///     .expect("set_write_timeout function failed");
+    /// assert_eq!(sock.write_timeout().unwrap(), Some(Duration::new(1, 0)));
+    /// ```
+    // #[stable(feature = "unix_socket", since = "1.10.0")]
+    pub fn write_timeout(&self) -> io::Result<Option<Duration>> {
+        self.inner.timeout(SO_SNDTIMEO)
+    }
+
+    /// Moves the socket into or out of nonblocking mode.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::os::windows::net::UnixDatagram;
+    ///
+    /// let sock = UnixDatagram::unbound().unwrap();
+    /// sock.set_nonblocking(true).expect("set_nonblocking function failed");
+    /// ```
+    // #[stable(feature = "unix_socket", since = "1.10.0")]
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        self.inner.set_nonblocking(nonblocking)?;
+        self.state.nonblocking.store(nonblocking, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Returns the value of the `SO_ERROR` option.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::os::windows::net::UnixDatagram;
+    ///
+    /// let sock = UnixDatagram::unbound().unwrap();
+    /// if let Ok(Some(err)) = sock.take_error() {
+    ///     println!("Got error: {:?}", err);
+    /// }
+    /// ```
+    // #[stable(feature = "unix_socket", since = "1.10.0")]
+    pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+        self.inner.take_error()
+    }
+
+    /// Shut down the read, write, or both halves of this connection.
+    ///
+    /// This function will cause all pending and future I/O calls on the
+    /// specified portions to immediately return with an appropriate value
+    /// (see the documentation of [`Shutdown`]).
+    ///
+    /// A [`recv`] blocked in another thread is woken by connecting to the
+    /// socket's own address, so this only reaches it if the socket is bound.
+    ///
+    /// [`Shutdown`]: ../../../../std/net/enum.Shutdown.html
+    /// [`recv`]: #method.recv
+    ///
+    /// ```ignore
+    /// use std::os::windows::net::UnixDatagram;
+    /// use std::net::Shutdown;
+    ///
+    /// let sock = UnixDatagram::unbound().unwrap();
+    /// sock.shutdown(Shutdown::Both).expect("shutdown function failed");
+    /// ```
+    // #[stable(feature = "unix_socket", since = "1.10.0")]
+    pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
+        if how != Shutdown::Read {
+            self.state.write_shutdown.store(true, Ordering::SeqCst);
+        }
+        if how != Shutdown::Write && !self.state.read_shutdown.swap(true, Ordering::SeqCst) {
+            // An empty connection is dropped as a cut-short frame, after
+            // which the receiver notices the shutdown. Failing to wake it
+            // doesn't undo the shutdown, so errors are ignored.
+            let local = &self.state.local;
+            let bound = match local.address() {
+                AddressKind::Unnamed => false,
+                _ => true,
+            };
+            if bound {
+                if let Ok(sock) = Socket::new_raw(AF_UNIX, SOCK_STREAM) {
+                    unsafe {
+                        connect(*sock.as_inner() as usize, &local.addr as *const _ as *const _,
+                                local.len);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// `UnixDatagram` has no raw socket conversions: its socket is only a listener
+// for the connections datagrams arrive on, and the connected peer, nonblocking
+// mode and shutdown state live in `DatagramState`. A raw socket could neither
+// carry that state out nor bring it back in.
+
+#[cfg(all(test, not(target_os = "emscripten")))]
+mod test {
+    use std::thread;
+    use std::io::{self, ErrorKind};
+    use std::io::prelude::*;
+    use std::time::Duration;
+    use sys_common::io::test::tmpdir;
+
+    use super::*;
+
+    macro_rules! or_panic {
+        ($e:expr) => {
+            match $e {
+                Ok(e) => e,
+                Err(e) => panic!("{}", e),
+            }
+        }
+    }
+
+    #[test]
+    fn basic() {
+        let dir = tmpdir();
+        let socket_path = dir.path().join("sock");
+        let msg1 = b"hello";
+        let msg2 = b"world!";
+
+        let listener = or_panic!(UnixListener::bind(&socket_path));
+        let thread = thread::spawn(move || {
+            let mut stream = or_panic!(listener.accept()).0;
+            let mut buf = [0; 5];
+            or_panic!(stream.read(&mut buf));
+            assert_eq!(&msg1[..], &buf[..]);
+            or_panic!(stream.write_all(msg2));
+        });
+
+        let mut stream = or_panic!(UnixStream::connect(&socket_path));
+        assert_eq!(Some(&*socket_path),
+                   stream.peer_addr().unwrap().as_pathname());
+        or_panic!(stream.write_all(msg1));
+        let mut buf = vec![];
+        or_panic!(stream.read_to_end(&mut buf));
+        assert_eq!(&msg2[..], &buf[..]);
+        drop(stream);
+
+        thread.join().unwrap();
+    }
+
+    #[test]
+    fn pair() {
+        let msg1 = b"hello";
+        let msg2 = b"world!";
+
+        let (mut s1, mut s2) = or_panic!(UnixStream::pair());
+        let thread = thread::spawn(move || {
+            // s1 must be moved in or the test will hang!
+            let mut buf = [0; 5];
+            or_panic!(s1.read(&mut buf));
+            assert_eq!(&msg1[..], &buf[..]);
+            or_panic!(s1.write_all(msg2));
+        });
+
+        or_panic!(s2.write_all(msg1));
+        let mut buf = vec![];
+        or_panic!(s2.read_to_end(&mut buf));
+        assert_eq!(&msg2[..], &buf[..]);
+        drop(s2);
+
+        thread.join().unwrap();
+    }
+
+    #[test]
+    fn try_clone() {
+        let dir = tmpdir();
+        let socket_path = dir.path().join("sock");
+        let msg1 = b"hello";
+        let msg2 = b"world";
+
+        let listener = or_panic!(UnixListener::bind(&socket_path));
+        let thread = thread::spawn(move || {
+            #[allow(unused_mut)]
+            let mut stream = or_panic!(listener.accept()).0;
+            or_panic!(stream.write_all(msg1));
+            or_panic!(stream.write_all(msg2));
+        });
+
+        let mut stream = or_panic!(UnixStream::connect(&socket_path));
+        let mut stream2 = or_panic!(stream.try_clone());
 
         let mut buf = [0; 5];
         or_panic!(stream.read(&mut buf));
@@ -1623,6 +2675,19 @@ mod test {
         thread.join().unwrap();
     }
 
+    #[test]
+    fn raw_socket_round_trip() {
+        let (s1, s2) = or_panic!(UnixStream::pair());
+        let raw = s1.as_raw_socket();
+        let mut s1 = unsafe { UnixStream::from_raw_socket(s1.into_raw_socket()) };
+        assert_eq!(raw, s1.as_raw_socket());
+
+        or_panic!((&s2).write_all(b"hello"));
+        let mut buf = [0; 5];
+        or_panic!(s1.read_exact(&mut buf));
+        assert_eq!(b"hello", &buf[..]);
+    }
+
     #[test]
     fn iter() {
         let dir = tmpdir();
@@ -1663,11 +2728,11 @@ mod test {
             Ok(_) => panic!("unexpected success"),
         }
 
-        // match UnixDatagram::bind(&socket_path) {
-        //     Err(ref e) if e.kind() == io::ErrorKind::InvalidInput => {}
-        //     Err(e) => panic!("unexpected error {}", e),
-        //     Ok(_) => panic!("unexpected success"),
-        // }
+        match UnixDatagram::bind(&socket_path) {
+            Err(ref e) if e.kind() == io::ErrorKind::InvalidInput => {}
+            Err(e) => panic!("unexpected error {}", e),
+            Ok(_) => panic!("unexpected success"),
+        }
     }
 
     #[test]
@@ -1755,125 +2820,495 @@ mod test {
         drop(listener);
     }
 
-    // #[test]
-    // fn test_unix_datagram() {
-    //     let dir = tmpdir();
-    //     let path1 = dir.path().join("sock1");
-    //     let path2 = dir.path().join("sock2");
-
-    //     let sock1 = or_panic!(UnixDatagram::bind(&path1));
-    //     let sock2 = or_panic!(UnixDatagram::bind(&path2));
-
-    //     let msg = b"hello world";
-    //     or_panic!(sock1.send_to(msg, &path2));
-    //     let mut buf = [0; 11];
-    //     or_panic!(sock2.recv_from(&mut buf));
-    //     assert_eq!(msg, &buf[..]);
-    // }
-
-    // #[test]
-    // fn test_unnamed_unix_datagram() {
-    //     let dir = tmpdir();
-    //     let path1 = dir.path().join("sock1");
-
-    //     let sock1 = or_panic!(UnixDatagram::bind(&path1));
-    //     let sock2 = or_panic!(UnixDatagram::unbound());
-
-    //     let msg = b"hello world";
-    //     or_panic!(sock2.send_to(msg, &path1));
-    //     let mut buf = [0; 11];
-    //     let (usize, addr) = or_panic!(sock1.recv_from(&mut buf));
-    //     assert_eq!(usize, 11);
-    //     assert!(addr.is_unnamed());
-    //     assert_eq!(msg, &buf[..]);
-    // }
-
-    // #[test]
-    // fn test_connect_unix_datagram() {
-    //     let dir = tmpdir();
-    //     let path1 = dir.path().join("sock1");
-    //     let path2 = dir.path().join("sock2");
-
-    //     let bsock1 = or_panic!(UnixDatagram::bind(&path1));
-    //     let bsock2 = or_panic!(UnixDatagram::bind(&path2));
-    //     let sock = or_panic!(UnixDatagram::unbound());
-    //     or_panic!(sock.connect(&path1));
-
-    //     // Check send()
-    //     let msg = b"hello there";
-    //     // or_panic!(sock.send(msg));
-    //     let mut buf = [0; 11];
-    //     let (usize, addr) = or_panic!(bsock1.recv_from(&mut buf));
-    //     assert_eq!(usize, 11);
-    //     assert!(addr.is_unnamed());
-    //     assert_eq!(msg, &buf[..]);
-
-    //     // Changing default socket works too
-    //     or_panic!(sock.connect(&path2));
-    //     // or_panic!(sock.send(msg));
-    //     or_panic!(bsock2.recv_from(&mut buf));
-    // }
-
-    // #[test]
-    // fn test_unix_datagram_recv() {
-    //     let dir = tmpdir();
-    //     let path1 = dir.path().join("sock1");
-
-    //     let sock1 = or_panic!(UnixDatagram::bind(&path1));
-    //     let sock2 = or_panic!(UnixDatagram::unbound());
-    //     or_panic!(sock2.connect(&path1));
-
-    //     let msg = b"hello world";
-    //     // or_panic!(sock2.send(msg));
-    //     let mut buf = [0; 11];
-    //     let size = or_panic!(sock1.recv(&mut buf));
-    //     assert_eq!(size, 11);
-    //     assert_eq!(msg, &buf[..]);
-    // }
-
-    // #[test]
-    // fn datagram_pair() {
-    //     let msg1 = b"hello";
-    //     let msg2 = b"world!";
-
-    //     let (s1, s2) = or_panic!(UnixDatagram::pair());
-    //     let thread = thread::spawn(move || {
-    //         // s1 must be moved in or the test will hang!
-    //         let mut buf = [0; 5];
-    //         or_panic!(s1.recv(&mut buf));
-    //         assert_eq!(&msg1[..], &buf[..]);
-    //         or_panic!(s1.send(msg2));
-    //     });
-
-    //     or_panic!(s2.send(msg1));
-    //     let mut buf = [0; 6];
-    //     or_panic!(s2.recv(&mut buf));
-    //     assert_eq!(&msg2[..], &buf[..]);
-    //     drop(s2);
-
-    //     thread.join().unwrap();
-    // }
-
-    // // Ensure the `set_read_timeout` and `set_write_timeout` calls return errors
-    // // when passed zero Durations
-    // #[test]
-    // fn test_unix_datagram_timeout_zero_duration() {
-    //     let dir = tmpdir();
-    //     let path = dir.path().join("sock");
-
-    //     let datagram = or_panic!(UnixDatagram::bind(&path));
-
-    //     let result = datagram.set_write_timeout(Some(Duration::new(0, 0)));
-    //     let err = result.unwrap_err();
-    //     assert_eq!(err.kind(), ErrorKind::InvalidInput);
-
-    //     let result = datagram.set_read_timeout(Some(Duration::new(0, 0)));
-    //     let err = result.unwrap_err();
-    //     assert_eq!(err.kind(), ErrorKind::InvalidInput);
-    // }
+    #[test]
+    fn test_unix_datagram() {
+        let dir = tmpdir();
+        let path1 = dir.path().join("sock1");
+        let path2 = dir.path().join("sock2");
+
+        let sock1 = or_panic!(UnixDatagram::bind(&path1));
+        let sock2 = or_panic!(UnixDatagram::bind(&path2));
+
+        let msg = b"hello world";
+        or_panic!(sock1.send_to(msg, &path2));
+        let mut buf = [0; 11];
+        or_panic!(sock2.recv_from(&mut buf));
+        assert_eq!(msg, &buf[..]);
+    }
+
+    #[test]
+    fn test_unnamed_unix_datagram() {
+        let dir = tmpdir();
+        let path1 = dir.path().join("sock1");
+
+        let sock1 = or_panic!(UnixDatagram::bind(&path1));
+        let sock2 = or_panic!(UnixDatagram::unbound());
+
+        let msg = b"hello world";
+        or_panic!(sock2.send_to(msg, &path1));
+        let mut buf = [0; 11];
+        let (usize, addr) = or_panic!(sock1.recv_from(&mut buf));
+        assert_eq!(usize, 11);
+        assert!(addr.is_unnamed());
+        assert_eq!(msg, &buf[..]);
+    }
+
+    #[test]
+    fn test_connect_unix_datagram() {
+        let dir = tmpdir();
+        let path1 = dir.path().join("sock1");
+        let path2 = dir.path().join("sock2");
+
+        let bsock1 = or_panic!(UnixDatagram::bind(&path1));
+        let bsock2 = or_panic!(UnixDatagram::bind(&path2));
+        let sock = or_panic!(UnixDatagram::unbound());
+        or_panic!(sock.connect(&path1));
+
+        // Check send()
+        let msg = b"hello there";
+        or_panic!(sock.send(msg));
+        let mut buf = [0; 11];
+        let (usize, addr) = or_panic!(bsock1.recv_from(&mut buf));
+        assert_eq!(usize, 11);
+        assert!(addr.is_unnamed());
+        assert_eq!(msg, &buf[..]);
+
+        // Changing default socket works too
+        or_panic!(sock.connect(&path2));
+        or_panic!(sock.send(msg));
+        or_panic!(bsock2.recv_from(&mut buf));
+    }
+
+    #[test]
+    fn test_unix_datagram_recv() {
+        let dir = tmpdir();
+        let path1 = dir.path().join("sock1");
+
+        let sock1 = or_panic!(UnixDatagram::bind(&path1));
+        let sock2 = or_panic!(UnixDatagram::unbound());
+        or_panic!(sock2.connect(&path1));
+
+        let msg = b"hello world";
+        or_panic!(sock2.send(msg));
+        let mut buf = [0; 11];
+        let size = or_panic!(sock1.recv(&mut buf));
+        assert_eq!(size, 11);
+        assert_eq!(msg, &buf[..]);
+    }
+
+    #[test]
+    fn test_unix_datagram_truncated() {
+        let dir = tmpdir();
+        let path1 = dir.path().join("sock1");
+        let path2 = dir.path().join("sock2");
+
+        let sock1 = or_panic!(UnixDatagram::bind(&path1));
+        let sock2 = or_panic!(UnixDatagram::bind(&path2));
+
+        or_panic!(sock2.send_to(b"hello world", &path1));
+        or_panic!(sock2.send_to(b"again", &path1));
+
+        let mut buf = [0; 5];
+        let (size, addr) = or_panic!(sock1.recv_from(&mut buf));
+        assert_eq!(size, 5);
+        assert_eq!(b"hello", &buf[..]);
+        assert_eq!(Some(&*path2), addr.as_pathname());
+
+        // The rest of the first datagram was discarded
+        let size = or_panic!(sock1.recv(&mut buf));
+        assert_eq!(b"again", &buf[..size]);
+    }
+
+    #[test]
+    fn test_unix_datagram_stalled_sender() {
+        let dir = tmpdir();
+        let path1 = dir.path().join("sock1");
+
+        let sock1 = or_panic!(UnixDatagram::bind(&path1));
+        or_panic!(sock1.set_read_timeout(Some(Duration::from_secs(5))));
+
+        // A sender that stops part way through its frame is dropped
+        let mut stalled = or_panic!(UnixStream::connect(&path1));
+        or_panic!(stalled.write_all(&[0]));
+
+        let sock2 = or_panic!(UnixDatagram::unbound());
+        or_panic!(sock2.send_to(b"hello", &path1));
+        let mut buf = [0; 5];
+        let size = or_panic!(sock1.recv(&mut buf));
+        assert_eq!(b"hello", &buf[..size]);
+    }
+
+    #[test]
+    fn test_unix_datagram_shutdown_wakes_recv() {
+        let dir = tmpdir();
+        let path1 = dir.path().join("sock1");
+
+        let sock1 = or_panic!(UnixDatagram::bind(&path1));
+        let sock2 = or_panic!(sock1.try_clone());
+        let thread = thread::spawn(move || {
+            let mut buf = [0; 5];
+            assert_eq!(or_panic!(sock2.recv(&mut buf)), 0);
+        });
+
+        thread::sleep(Duration::from_millis(100));
+        or_panic!(sock1.shutdown(Shutdown::Read));
+        thread.join().unwrap();
+    }
+
+    #[test]
+    fn test_unix_datagram_bind_backlog() {
+        let dir = tmpdir();
+        let path1 = dir.path().join("sock1");
+
+        let sock1 = or_panic!(UnixDatagram::bind_backlog(&path1, 1));
+        let sock2 = or_panic!(UnixDatagram::unbound());
+        or_panic!(sock2.send_to(b"hello", &path1));
+        let mut buf = [0; 5];
+        assert_eq!(or_panic!(sock1.recv(&mut buf)), 5);
+    }
+
+    #[test]
+    fn datagram_pair() {
+        let msg1 = b"hello";
+        let msg2 = b"world!";
+
+        let (s1, s2) = or_panic!(UnixDatagram::pair());
+        let thread = thread::spawn(move || {
+            // s1 must be moved in or the test will hang!
+            let mut buf = [0; 5];
+            or_panic!(s1.recv(&mut buf));
+            assert_eq!(&msg1[..], &buf[..]);
+            or_panic!(s1.send(msg2));
+        });
+
+        or_panic!(s2.send(msg1));
+        let mut buf = [0; 6];
+        or_panic!(s2.recv(&mut buf));
+        assert_eq!(&msg2[..], &buf[..]);
+        drop(s2);
+
+        thread.join().unwrap();
+    }
+
+    // Ensure the `set_read_timeout` and `set_write_timeout` calls return errors
+    // when passed zero Durations
+    #[test]
+    fn test_unix_datagram_timeout_zero_duration() {
+        let dir = tmpdir();
+        let path = dir.path().join("sock");
+
+        let datagram = or_panic!(UnixDatagram::bind(&path));
+
+        let result = datagram.set_write_timeout(Some(Duration::new(0, 0)));
+        let err = result.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+        let result = datagram.set_read_timeout(Some(Duration::new(0, 0)));
+        let err = result.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
 
     #[test]
     fn abstract_namespace_not_allowed() {
         assert!(UnixStream::connect("\0asdf").is_err());
     }
+
+    #[test]
+    fn abstract_address() {
+        let name = format!("rust-abstract-{}", ::std::process::id());
+        let addr = or_panic!(SocketAddr::from_abstract_name(&name));
+        assert_eq!(Some(name.as_bytes()), addr.as_abstract_name());
+        assert_eq!(None, addr.as_pathname());
+        assert!(!addr.is_unnamed());
+
+        let listener = or_panic!(UnixListener::bind_addr(&addr));
+        let local = or_panic!(listener.local_addr());
+        assert_eq!(Some(name.as_bytes()), local.as_abstract_name());
+
+        let thread = thread::spawn(move || {
+            let mut stream = or_panic!(listener.accept()).0;
+            or_panic!(stream.write_all(b"hello"));
+        });
+
+        let mut stream = or_panic!(UnixStream::connect_addr(&addr));
+        let mut buf = vec![];
+        or_panic!(stream.read_to_end(&mut buf));
+        assert_eq!(b"hello", &buf[..]);
+
+        thread.join().unwrap();
+    }
+
+    #[test]
+    fn pathname_address() {
+        let dir = tmpdir();
+        let socket_path = dir.path().join("sock");
+
+        let addr = or_panic!(SocketAddr::from_pathname(&socket_path));
+        assert_eq!(Some(&*socket_path), addr.as_pathname());
+
+        let listener = or_panic!(UnixListener::bind(&socket_path));
+        let local = or_panic!(listener.local_addr());
+        assert_eq!(addr, local);
+
+        let other = or_panic!(SocketAddr::from_pathname(dir.path().join("other")));
+        assert!(addr != other);
+
+        let mut set = ::std::collections::HashSet::new();
+        set.insert(addr.clone());
+        assert!(set.contains(&local));
+        assert!(!set.contains(&other));
+    }
+
+    #[test]
+    fn pathname_address_invalid() {
+        match SocketAddr::from_pathname("sock\0path") {
+            Err(ref e) if e.kind() == io::ErrorKind::InvalidInput => {}
+            Err(e) => panic!("unexpected error {}", e),
+            Ok(_) => panic!("unexpected success"),
+        }
+    }
+
+    #[test]
+    fn vectored() {
+        let (s1, s2) = or_panic!(UnixStream::pair());
+
+        let n = or_panic!(s1.write_vectored(&[b"hello", b" ", b"world"]));
+        assert_eq!(n, 11);
+
+        let mut a = [0; 6];
+        let mut b = [0; 10];
+        let n = or_panic!(s2.read_vectored(&mut [&mut a, &mut b]));
+        assert_eq!(n, 11);
+        assert_eq!(&a, b"hello ");
+        assert_eq!(&b[..5], b"world");
+    }
+
+    #[test]
+    fn peer_cred() {
+        let (s1, s2) = or_panic!(UnixStream::pair());
+
+        let cred = or_panic!(s1.peer_cred());
+        assert_eq!(cred.pid, ::std::process::id());
+        assert_eq!(cred, or_panic!(s2.peer_cred()));
+    }
+
+    #[test]
+    fn linger() {
+        let (s1, _s2) = or_panic!(UnixStream::pair());
+
+        assert_eq!(None, or_panic!(s1.linger()));
+        or_panic!(s1.set_linger(Some(Duration::from_secs(5))));
+        assert_eq!(Some(Duration::from_secs(5)), or_panic!(s1.linger()));
+        or_panic!(s1.set_linger(None));
+        assert_eq!(None, or_panic!(s1.linger()));
+    }
+
+    #[test]
+    fn bind_reclaim() {
+        let dir = tmpdir();
+        let socket_path = dir.path().join("sock");
+
+        drop(or_panic!(UnixListener::bind(&socket_path)));
+        match UnixListener::bind(&socket_path) {
+            Err(ref e) if e.kind() == ErrorKind::AddrInUse => {}
+            Err(e) => panic!("unexpected error {}", e),
+            Ok(_) => panic!("unexpected success"),
+        }
+
+        let listener = or_panic!(UnixListener::bind_reclaim(&socket_path));
+        match UnixListener::bind_reclaim(&socket_path) {
+            Err(ref e) if e.kind() == ErrorKind::AddrInUse => {}
+            Err(e) => panic!("unexpected error {}", e),
+            Ok(_) => panic!("unexpected success"),
+        }
+
+        let thread = thread::spawn(move || {
+            or_panic!(listener.accept());
+        });
+        or_panic!(UnixStream::connect(&socket_path));
+        thread.join().unwrap();
+    }
+
+    #[test]
+    fn bind_reclaim_regular_file() {
+        let dir = tmpdir();
+        let path = dir.path().join("sock");
+        or_panic!(or_panic!(fs::File::create(&path)).write_all(b"keep me"));
+
+        match UnixListener::bind_reclaim(&path) {
+            Err(ref e) if e.kind() == ErrorKind::AddrInUse => {}
+            Err(e) => panic!("unexpected error {}", e),
+            Ok(_) => panic!("unexpected success"),
+        }
+        let mut contents = Vec::new();
+        or_panic!(or_panic!(fs::File::open(&path)).read_to_end(&mut contents));
+        assert_eq!(contents, b"keep me");
+    }
+
+    #[test]
+    fn unlink_on_drop() {
+        let dir = tmpdir();
+        let socket_path = dir.path().join("sock");
+
+        let listener = or_panic!(UnixListener::bind(&socket_path));
+        or_panic!(listener.set_unlink_on_drop(true));
+        let clone = or_panic!(listener.try_clone());
+        drop(listener);
+        assert!(fs::symlink_metadata(&socket_path).is_ok());
+        drop(clone);
+        assert!(fs::symlink_metadata(&socket_path).is_err());
+
+        let listener = or_panic!(UnixListener::bind(&socket_path));
+        or_panic!(listener.set_unlink_on_drop(true));
+        or_panic!(listener.set_unlink_on_drop(false));
+        drop(listener);
+        assert!(fs::symlink_metadata(&socket_path).is_ok());
+    }
+
+    #[test]
+    fn unlink_on_drop_abstract() {
+        let name = format!("rust-unlink-{}", ::std::process::id());
+        let addr = or_panic!(SocketAddr::from_abstract_name(&name));
+        let listener = or_panic!(UnixListener::bind_addr(&addr));
+        match listener.set_unlink_on_drop(true) {
+            Err(ref e) if e.kind() == ErrorKind::InvalidInput => {}
+            Err(e) => panic!("unexpected error {}", e),
+            Ok(_) => panic!("unexpected success"),
+        }
+    }
+
+    #[test]
+    fn listener_builder() {
+        let dir = tmpdir();
+        let socket_path = dir.path().join("a").join("b").join("sock");
+
+        assert!(UnixListenerBuilder::new().bind(&socket_path).is_err());
+
+        let listener = or_panic!(UnixListenerBuilder::new()
+            .backlog(1)
+            .security_descriptor("D:P(A;;GA;;;WD)")
+            .create_parent_dirs(true)
+            .unlink_on_drop(true)
+            .bind(&socket_path));
+
+        let thread = thread::spawn(move || {
+            let mut stream = or_panic!(listener.accept()).0;
+            or_panic!(stream.write_all(b"hello"));
+        });
+
+        let mut stream = or_panic!(UnixStream::connect(&socket_path));
+        let mut buf = vec![];
+        or_panic!(stream.read_to_end(&mut buf));
+        assert_eq!(b"hello", &buf[..]);
+
+        thread.join().unwrap();
+        assert!(fs::symlink_metadata(&socket_path).is_err());
+    }
+
+    #[test]
+    fn listener_builder_bad_security_descriptor() {
+        let dir = tmpdir();
+        let socket_path = dir.path().join("sock");
+
+        assert!(UnixListenerBuilder::new()
+            .security_descriptor("not a descriptor")
+            .bind(&socket_path)
+            .is_err());
+        assert!(fs::symlink_metadata(&socket_path).is_err());
+    }
+
+    #[test]
+    fn accept_timeout() {
+        let dir = tmpdir();
+        let socket_path = dir.path().join("sock");
+
+        let listener = or_panic!(UnixListener::bind(&socket_path));
+        match listener.accept_timeout(Duration::from_millis(100)) {
+            Err(ref e) if e.kind() == ErrorKind::TimedOut => {}
+            Err(e) => panic!("unexpected error {}", e),
+            Ok(_) => panic!("unexpected success"),
+        }
+
+        let err = listener.accept_timeout(Duration::new(0, 0)).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+        let _stream = or_panic!(UnixStream::connect_timeout(&socket_path,
+                                                            Duration::from_secs(1)));
+        let (mut accepted, _) = or_panic!(listener.accept_timeout(Duration::from_secs(1)));
+
+        // Neither the listener nor the accepted stream are left nonblocking
+        or_panic!(accepted.set_read_timeout(Some(Duration::from_millis(100))));
+        match accepted.read(&mut [0]) {
+            Err(ref e) if e.kind() == ErrorKind::TimedOut => {}
+            Err(e) => panic!("unexpected error {}", e),
+            Ok(_) => panic!("unexpected success"),
+        }
+        let thread = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            or_panic!(UnixStream::connect(&socket_path));
+        });
+        or_panic!(listener.accept());
+        thread.join().unwrap();
+    }
+
+    #[test]
+    fn connect_timeout_no_listener() {
+        let dir = tmpdir();
+        let socket_path = dir.path().join("sock");
+
+        assert!(UnixStream::connect_timeout(&socket_path, Duration::from_secs(1)).is_err());
+    }
+
+    #[test]
+    fn send_socket() {
+        let (a, b) = or_panic!(UnixStream::pair());
+        let (c, mut d) = or_panic!(UnixStream::pair());
+
+        or_panic!(a.send_socket(&c));
+        let mut c2: UnixStream = or_panic!(b.recv_socket());
+        drop(c);
+
+        or_panic!(c2.write_all(b"hello"));
+        let mut buf = [0; 5];
+        or_panic!(d.read_exact(&mut buf));
+        assert_eq!(&buf, b"hello");
+    }
+
+    #[test]
+    fn recv_socket_short_read() {
+        let (a, b) = or_panic!(UnixStream::pair());
+
+        or_panic!((&a).write_all(b"not a socket"));
+        drop(a);
+        match b.recv_socket::<UnixStream>() {
+            Err(ref e) if e.kind() == io::ErrorKind::InvalidData => {}
+            Err(e) => panic!("unexpected error {}", e),
+            Ok(_) => panic!("unexpected success"),
+        }
+    }
+
+    #[test]
+    fn abstract_name_too_long() {
+        let name = [b'a'; 200];
+        match SocketAddr::from_abstract_name(&name[..]) {
+            Err(ref e) if e.kind() == io::ErrorKind::InvalidInput => {}
+            Err(e) => panic!("unexpected error {}", e),
+            Ok(_) => panic!("unexpected success"),
+        }
+    }
+
+    #[test]
+    fn abstract_name_all_nulls() {
+        for name in &[&b""[..], &b"\0\0"[..]] {
+            match SocketAddr::from_abstract_name(name) {
+                Err(ref e) if e.kind() == io::ErrorKind::InvalidInput => {}
+                Err(e) => panic!("unexpected error {}", e),
+                Ok(_) => panic!("unexpected success"),
+            }
+        }
+
+        let addr = or_panic!(SocketAddr::from_abstract_name(b"\0\0a"));
+        assert_eq!(Some(&b"\0\0a"[..]), addr.as_abstract_name());
+        assert!(!addr.is_unnamed());
+    }
 }
@@ -16,14 +16,17 @@ use std::cmp;
 use std::io::{self, Read};
 use libc::{c_int, c_void, c_ulong, c_long};
 use std::mem;
-use net::{SocketAddr, Shutdown};
+use net::{SocketAddr, Shutdown, IpAddr, Ipv4Addr, Ipv6Addr, KeepaliveParams, PacketInfo};
+use net::{Dscp, TcpInfo, UdpEvent};
 use std::ptr;
+use std::slice;
 use std::sync::Once;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use sys::c;
 use sys;
 use sys_common::{self, AsInner, FromInner, IntoInner};
 use sys_common::net;
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 pub type wrlen_t = i32;
 
@@ -100,6 +103,22 @@ pub fn cvt_r<T, F>(mut f: F) -> io::Result<T>
     cvt(f())
 }
 
+fn dur2timeval(dur: Duration) -> io::Result<c::timeval> {
+    if dur.as_secs() == 0 && dur.subsec_nanos() == 0 {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                  "cannot set a 0 duration timeout"));
+    }
+
+    let mut timeout = c::timeval {
+        tv_sec: dur.as_secs() as c_long,
+        tv_usec: (dur.subsec_nanos() / 1000) as c_long,
+    };
+    if timeout.tv_sec == 0 && timeout.tv_usec == 0 {
+        timeout.tv_usec = 1;
+    }
+    Ok(timeout)
+}
+
 impl Socket {
     pub fn new(addr: &SocketAddr, ty: c_int) -> io::Result<Socket> {
         let fam = match *addr {
@@ -121,15 +140,21 @@ impl Socket {
         Ok(socket)
     }
 
-    // socketpair() not supported on Windows
+    // socketpair() not supported on Windows; `UnixStream::pair` and
+    // `UnixDatagram::pair` emulate it with a rendezvous on a temporary path.
     // pub fn new_pair(fam: c_int, ty: c_int) -> io::Result<(Socket, Socket)> { ... }
 
     pub fn connect_timeout(&self, addr: &SocketAddr, timeout: Duration) -> io::Result<()> {
+        let (addrp, len) = addr.into_inner();
+        self.connect_raw_timeout(addrp, len, timeout)
+    }
+
+    /// Like `connect_timeout`, but for an address of any family, such as the
+    /// `sockaddr_un` of a Unix socket.
+    pub fn connect_raw_timeout(&self, addrp: *const c::SOCKADDR, len: c_int,
+                               timeout: Duration) -> io::Result<()> {
         self.set_nonblocking(true)?;
-        let r = unsafe {
-            let (addrp, len) = addr.into_inner();
-            cvt(c::connect(self.0, addrp, len))
-        };
+        let r = unsafe { cvt(c::connect(self.0, addrp, len)) };
         self.set_nonblocking(false)?;
 
         match r {
@@ -138,25 +163,8 @@ impl Socket {
             Err(e) => return Err(e),
         }
 
-        if timeout.as_secs() == 0 && timeout.subsec_nanos() == 0 {
-            return Err(io::Error::new(io::ErrorKind::InvalidInput,
-                                      "cannot set a 0 duration timeout"));
-        }
-
-        let mut timeout = c::timeval {
-            tv_sec: timeout.as_secs() as c_long,
-            tv_usec: (timeout.subsec_nanos() / 1000) as c_long,
-        };
-        if timeout.tv_sec == 0 && timeout.tv_usec == 0 {
-            timeout.tv_usec = 1;
-        }
-
-        let fds = unsafe {
-            let mut fds = mem::zeroed::<c::fd_set>();
-            fds.fd_count = 1;
-            fds.fd_array[0] = self.0;
-            fds
-        };
+        let timeout = dur2timeval(timeout)?;
+        let fds = self.fd_set();
 
         let mut writefds = fds;
         let mut errorfds = fds;
@@ -178,6 +186,151 @@ impl Socket {
         }
     }
 
+    /// Blocks until the socket becomes readable, which for a listening socket
+    /// means that a connection is ready to be accepted. Returns a `TimedOut`
+    /// error if that doesn't happen within `timeout`.
+    pub fn wait_readable(&self, timeout: Duration) -> io::Result<()> {
+        let timeout = dur2timeval(timeout)?;
+        let fds = self.fd_set();
+
+        let mut readfds = fds;
+        let mut errorfds = fds;
+
+        let n = unsafe {
+            cvt(c::select(1, &mut readfds, ptr::null_mut(), &mut errorfds, &timeout))?
+        };
+
+        match n {
+            0 => Err(io::Error::new(io::ErrorKind::TimedOut, "operation timed out")),
+            _ => {
+                if readfds.fd_count != 1 {
+                    if let Some(e) = self.take_error()? {
+                        return Err(e);
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Connects a stream socket to one of `addrs`, starting a connection
+    /// attempt to each address in order, `delay` after the previous one or as
+    /// soon as it fails, and returning the first socket to connect. The other
+    /// attempts are abandoned.
+    pub fn connect_race(addrs: &[SocketAddr], delay: Duration,
+                        timeout: Option<Duration>) -> io::Result<Socket> {
+        let start = Instant::now();
+        let deadline = timeout.map(|timeout| start + timeout);
+        let mut pending: Vec<Socket> = Vec::new();
+        let mut next = 0;
+        let mut next_attempt = start;
+        let mut last_err = None;
+
+        loop {
+            let now = Instant::now();
+            if deadline.map_or(false, |deadline| now >= deadline) {
+                return Err(io::Error::new(io::ErrorKind::TimedOut, "connection timed out"));
+            }
+
+            // `select` can't wait on more than FD_SETSIZE sockets at once.
+            if next < addrs.len() && pending.len() < c::FD_SETSIZE &&
+                (now >= next_attempt || pending.is_empty()) {
+                let addr = &addrs[next];
+                next += 1;
+                let sock = match Socket::new(addr, c::SOCK_STREAM) {
+                    Ok(sock) => sock,
+                    Err(e) => {
+                        last_err = Some(e);
+                        continue;
+                    }
+                };
+                let r = sock.set_nonblocking(true).and_then(|_| {
+                    let (addrp, len) = addr.into_inner();
+                    cvt(unsafe { c::connect(sock.0, addrp, len) })
+                });
+                match r {
+                    Ok(_) => {
+                        sock.set_nonblocking(false)?;
+                        return Ok(sock);
+                    }
+                    Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => pending.push(sock),
+                    Err(e) => {
+                        last_err = Some(e);
+                        continue;
+                    }
+                }
+                next_attempt = now + delay;
+                continue;
+            }
+
+            if pending.is_empty() {
+                return Err(last_err.unwrap_or_else(|| {
+                    io::Error::new(io::ErrorKind::InvalidInput,
+                                   "could not resolve to any addresses")
+                }));
+            }
+
+            // Wait for an attempt to finish, but no longer than until the next
+            // attempt is due or the deadline passes.
+            let mut wake = deadline;
+            if next < addrs.len() && pending.len() < c::FD_SETSIZE {
+                wake = Some(wake.map_or(next_attempt, |w| cmp::min(w, next_attempt)));
+            }
+            let timeout = match wake {
+                Some(wake) if wake <= now => continue,
+                Some(wake) => Some(dur2timeval(wake - now)?),
+                None => None,
+            };
+
+            let mut writefds: c::fd_set = unsafe { mem::zeroed() };
+            for sock in &pending {
+                writefds.fd_array[writefds.fd_count as usize] = sock.0;
+                writefds.fd_count += 1;
+            }
+            let mut errorfds = writefds;
+
+            let n = unsafe {
+                cvt(c::select(1, ptr::null_mut(), &mut writefds, &mut errorfds,
+                              timeout.as_ref().map_or(ptr::null(), |t| t as *const _)))?
+            };
+            if n == 0 {
+                continue;
+            }
+
+            let mut i = 0;
+            while i < pending.len() {
+                let fd = pending[i].0;
+                let ready = |fds: &c::fd_set| {
+                    fds.fd_array[..fds.fd_count as usize].iter().any(|&s| s == fd)
+                };
+                if ready(&errorfds) {
+                    // A failed attempt lets the next one start right away.
+                    let sock = pending.remove(i);
+                    last_err = Some(match sock.take_error()? {
+                        Some(e) => e,
+                        None => io::Error::new(io::ErrorKind::Other, "connect failed"),
+                    });
+                    next_attempt = now;
+                } else if ready(&writefds) {
+                    let sock = pending.swap_remove(i);
+                    sock.set_nonblocking(false)?;
+                    return Ok(sock);
+                } else {
+                    i += 1;
+                }
+            }
+        }
+    }
+
+    fn fd_set(&self) -> c::fd_set {
+        unsafe {
+            let mut fds = mem::zeroed::<c::fd_set>();
+            fds.fd_count = 1;
+            fds.fd_array[0] = self.0;
+            fds
+        }
+    }
+
     pub fn accept(&self, storage: *mut c::SOCKADDR,
                   len: *mut c_int) -> io::Result<Socket> {
         let socket = unsafe {
@@ -191,15 +344,29 @@ impl Socket {
     }
 
     pub fn duplicate(&self) -> io::Result<Socket> {
-        let socket = unsafe {
+        let mut info = Socket::duplicate_for(self.0, unsafe { c::GetCurrentProcessId() })?;
+        Socket::from_protocol_info(&mut info)
+    }
+
+    /// Returns the information process `pid` needs to create its own socket
+    /// for the same connection as `socket` with `from_protocol_info`.
+    ///
+    /// This takes a raw socket so that sockets which are only borrowed can be
+    /// duplicated without wrapping them in an owning `Socket`.
+    pub fn duplicate_for(socket: c::SOCKET, pid: c::DWORD) -> io::Result<c::WSAPROTOCOL_INFO> {
+        unsafe {
             let mut info: c::WSAPROTOCOL_INFO = mem::zeroed();
-            cvt(c::WSADuplicateSocketW(self.0,
-                                            c::GetCurrentProcessId(),
-                                            &mut info))?;
+            cvt(c::WSADuplicateSocketW(socket, pid, &mut info))?;
+            Ok(info)
+        }
+    }
+
+    pub fn from_protocol_info(info: &mut c::WSAPROTOCOL_INFO) -> io::Result<Socket> {
+        let socket = unsafe {
             match c::WSASocketW(info.iAddressFamily,
                                 info.iSocketType,
                                 info.iProtocol,
-                                &mut info, 0,
+                                info, 0,
                                 c::WSA_FLAG_OVERLAPPED) {
                 c::INVALID_SOCKET => Err(last_error()),
                 n => Ok(Socket(n)),
@@ -230,6 +397,45 @@ impl Socket {
         self.recv_with_flags(buf, c::MSG_PEEK)
     }
 
+    pub fn read_vectored(&self, bufs: &mut [&mut [u8]]) -> io::Result<usize> {
+        let mut wsabufs = wsabufs(bufs.iter_mut().map(|buf| (buf.as_mut_ptr(), buf.len())));
+        let mut nread: c::DWORD = 0;
+        let mut flags: c::DWORD = 0;
+
+        // Shut down sockets return EOF, just like `recv_with_flags`.
+        unsafe {
+            match c::WSARecv(self.0,
+                             wsabufs.as_mut_ptr(),
+                             wsabufs.len() as c::DWORD,
+                             &mut nread,
+                             &mut flags,
+                             ptr::null_mut(),
+                             None) {
+                c::SOCKET_ERROR if c::WSAGetLastError() == c::WSAESHUTDOWN => Ok(0),
+                c::SOCKET_ERROR => Err(last_error()),
+                _ => Ok(nread as usize)
+            }
+        }
+    }
+
+    pub fn write_vectored(&self, bufs: &[&[u8]]) -> io::Result<usize> {
+        // `WSASend` only reads through the pointers, despite their type.
+        let mut wsabufs = wsabufs(bufs.iter().map(|buf| (buf.as_ptr() as *mut u8, buf.len())));
+        let mut nwritten: c::DWORD = 0;
+        unsafe {
+            match c::WSASend(self.0,
+                             wsabufs.as_mut_ptr(),
+                             wsabufs.len() as c::DWORD,
+                             &mut nwritten,
+                             0,
+                             ptr::null_mut(),
+                             None) {
+                c::SOCKET_ERROR => Err(last_error()),
+                _ => Ok(nwritten as usize)
+            }
+        }
+    }
+
     fn recv_from_with_flags(&self, buf: &mut [u8], flags: c_int)
                             -> io::Result<(usize, SocketAddr)> {
         let mut storage: c::SOCKADDR_STORAGE_LH = unsafe { mem::zeroed() };
@@ -262,6 +468,330 @@ impl Socket {
         self.recv_from_with_flags(buf, c::MSG_PEEK)
     }
 
+    /// Like `recv_from`, but reports a peer whose port was unreachable as an
+    /// event instead of failing with `WSAECONNRESET`.
+    ///
+    /// Winsock fills in the address of the unreachable peer when it can; it
+    /// is left out of the event otherwise.
+    pub fn recv_event(&self, buf: &mut [u8]) -> io::Result<UdpEvent> {
+        let mut storage: c::SOCKADDR_STORAGE_LH = unsafe { mem::zeroed() };
+        let mut addrlen = mem::size_of_val(&storage) as c::socklen_t;
+        let len = cmp::min(buf.len(), <wrlen_t>::max_value() as usize) as wrlen_t;
+
+        unsafe {
+            match c::recvfrom(self.0,
+                              buf.as_mut_ptr() as *mut c_void,
+                              len,
+                              0,
+                              &mut storage as *mut _ as *mut _,
+                              &mut addrlen) {
+                -1 if c::WSAGetLastError() == c::WSAESHUTDOWN => {
+                    let addr = net::sockaddr_to_addr(&storage, addrlen as usize)?;
+                    Ok(UdpEvent::Datagram(0, addr))
+                }
+                -1 if c::WSAGetLastError() == c::WSAECONNRESET => {
+                    let addr = net::sockaddr_to_addr(&storage, addrlen as usize).ok();
+                    Ok(UdpEvent::Unreachable(addr))
+                }
+                -1 => Err(last_error()),
+                n => {
+                    let addr = net::sockaddr_to_addr(&storage, addrlen as usize)?;
+                    Ok(UdpEvent::Datagram(n as usize, addr))
+                }
+            }
+        }
+    }
+
+    pub fn set_connreset(&self, report: bool) -> io::Result<()> {
+        self.ioctl(c::SIO_UDP_CONNRESET, &(report as c::BOOL), &mut ())
+    }
+
+    fn recv_from_wsa(&self, buf: &mut [u8], flags: c::DWORD)
+                     -> io::Result<(usize, SocketAddr)> {
+        let mut storage: c::SOCKADDR_STORAGE_LH = unsafe { mem::zeroed() };
+        let mut addrlen = mem::size_of_val(&storage) as c_int;
+        let mut wsabuf = c::WSABUF {
+            len: cmp::min(buf.len(), c::DWORD::max_value() as usize) as c::ULONG,
+            buf: buf.as_mut_ptr() as *mut c::CHAR,
+        };
+        let mut nread: c::DWORD = 0;
+        let mut flags = flags;
+
+        // Shut down sockets return EOF, just like `recv_from_with_flags`.
+        unsafe {
+            match c::WSARecvFrom(self.0,
+                                 &mut wsabuf,
+                                 1,
+                                 &mut nread,
+                                 &mut flags,
+                                 &mut storage as *mut _ as *mut _,
+                                 &mut addrlen,
+                                 ptr::null_mut(),
+                                 None) {
+                c::SOCKET_ERROR if c::WSAGetLastError() == c::WSAESHUTDOWN => {
+                    Ok((0, net::sockaddr_to_addr(&storage, addrlen as usize)?))
+                }
+                c::SOCKET_ERROR => Err(last_error()),
+                _ => Ok((nread as usize, net::sockaddr_to_addr(&storage, addrlen as usize)?)),
+            }
+        }
+    }
+
+    /// Receives a datagram into each of `bufs` in turn, for as long as more
+    /// datagrams are queued. Only the first receive blocks.
+    ///
+    /// Winsock has no `recvmmsg`, so this is a loop over `WSARecvFrom`. The
+    /// socket's mode is left alone, since clones share it; instead each
+    /// further datagram is only received once `select` says one is queued and
+    /// peeking at it into its buffer succeeds. A datagram that would fail to
+    /// be received, such as one too long for its buffer, is left queued for
+    /// the next call to report, so an error never costs the datagrams already
+    /// received. A clone receiving at the same time can still take the queued
+    /// datagram between the peek and the receive, blocking the batch until
+    /// the next one arrives.
+    pub fn recv_batch(&self, bufs: &mut [&mut [u8]])
+                      -> io::Result<Vec<(usize, SocketAddr)>> {
+        let mut msgs = Vec::with_capacity(bufs.len());
+        for buf in bufs.iter_mut() {
+            if !msgs.is_empty() {
+                match self.poll_readable() {
+                    Ok(true) => {}
+                    Ok(false) | Err(..) => break,
+                }
+                if self.recv_from_wsa(buf, c::MSG_PEEK as c::DWORD).is_err() {
+                    break
+                }
+            }
+            match self.recv_from_wsa(buf, 0) {
+                Ok(msg) => msgs.push(msg),
+                Err(e) => {
+                    if msgs.is_empty() {
+                        return Err(e)
+                    }
+                    break
+                }
+            }
+        }
+        Ok(msgs)
+    }
+
+    // Whether a datagram (or an error) is waiting to be received, without
+    // blocking.
+    fn poll_readable(&self) -> io::Result<bool> {
+        let timeout = c::timeval { tv_sec: 0, tv_usec: 0 };
+        let mut readfds = self.fd_set();
+        let n = unsafe {
+            cvt(c::select(1, &mut readfds, ptr::null_mut(), ptr::null_mut(), &timeout))?
+        };
+        Ok(n != 0)
+    }
+
+    /// Sends each of `bufs` to the matching address in `addrs`, returning how
+    /// many datagrams were sent.
+    ///
+    /// Like `recv_batch`, this is a loop over `WSASendTo`. An error after the
+    /// first datagram ends the batch early, before the datagram that failed,
+    /// so sending the rest of the batch again reports it.
+    pub fn send_batch(&self, bufs: &[&[u8]], addrs: &[SocketAddr]) -> io::Result<usize> {
+        let mut sent = 0;
+        for (buf, addr) in bufs.iter().zip(addrs) {
+            let (dstp, dstlen) = addr.into_inner();
+            let mut wsabuf = c::WSABUF {
+                len: cmp::min(buf.len(), c::DWORD::max_value() as usize) as c::ULONG,
+                buf: buf.as_ptr() as *mut c::CHAR,
+            };
+            let mut nsent: c::DWORD = 0;
+            let r = unsafe {
+                c::WSASendTo(self.0, &mut wsabuf, 1, &mut nsent, 0, dstp, dstlen,
+                             ptr::null_mut(), None)
+            };
+            if r == c::SOCKET_ERROR {
+                if sent == 0 {
+                    return Err(last_error())
+                }
+                break
+            }
+            sent += 1;
+        }
+        Ok(sent)
+    }
+
+    // `WSARecvMsg` isn't exported by ws2_32, it has to be looked up with
+    // `WSAIoctl` instead. The pointer is the same for every socket so it is
+    // only looked up once.
+    fn wsarecvmsg(&self) -> io::Result<c::LPFN_WSARECVMSG> {
+        static PTR: AtomicUsize = AtomicUsize::new(0);
+
+        let mut addr = PTR.load(Ordering::SeqCst);
+        if addr == 0 {
+            self.ioctl(c::SIO_GET_EXTENSION_FUNCTION_POINTER, &c::WSAID_WSARECVMSG,
+                       &mut addr)?;
+            PTR.store(addr, Ordering::SeqCst);
+        }
+        Ok(unsafe { mem::transmute::<usize, c::LPFN_WSARECVMSG>(addr) })
+    }
+
+    /// Like `recv_from_with_flags`, but also receives the control messages
+    /// the socket has been asked to deliver into `control`, returning how many
+    /// bytes of it were filled in.
+    ///
+    /// A datagram that doesn't fit in `buf` fails with `WSAEMSGSIZE`, just as
+    /// with `recvfrom`; so do control messages that don't fit in `control`,
+    /// rather than going missing.
+    fn recv_msg_with_flags(&self, buf: &mut [u8], control: &mut [u8], flags: c_int)
+                           -> io::Result<(usize, SocketAddr, usize)> {
+        let wsarecvmsg = self.wsarecvmsg()?;
+        let mut storage: c::SOCKADDR_STORAGE_LH = unsafe { mem::zeroed() };
+        let mut wsabuf = c::WSABUF {
+            len: cmp::min(buf.len(), c::DWORD::max_value() as usize) as c::ULONG,
+            buf: buf.as_mut_ptr() as *mut c::CHAR,
+        };
+        let mut msg = c::WSAMSG {
+            name: &mut storage as *mut _ as *mut _,
+            namelen: mem::size_of_val(&storage) as c_int,
+            lpBuffers: &mut wsabuf,
+            dwBufferCount: 1,
+            Control: c::WSABUF {
+                len: control.len() as c::ULONG,
+                buf: control.as_mut_ptr() as *mut c::CHAR,
+            },
+            dwFlags: flags as c::ULONG,
+        };
+        let mut nread: c::DWORD = 0;
+
+        // On unix when a socket is shut down all further reads return 0, so we
+        // do the same on windows to map a shut down socket to returning EOF.
+        unsafe {
+            match wsarecvmsg(self.0, &mut msg, &mut nread, ptr::null_mut(), None) {
+                -1 if c::WSAGetLastError() == c::WSAESHUTDOWN => {
+                    let addr = net::sockaddr_to_addr(&storage, msg.namelen as usize)?;
+                    Ok((0, addr, 0))
+                }
+                -1 => Err(last_error()),
+                _ if msg.dwFlags & (c::MSG_TRUNC | c::MSG_CTRUNC) as c::ULONG != 0 => {
+                    Err(io::Error::from_raw_os_error(c::WSAEMSGSIZE))
+                }
+                _ => {
+                    let addr = net::sockaddr_to_addr(&storage, msg.namelen as usize)?;
+                    Ok((nread as usize, addr, msg.Control.len as usize))
+                }
+            }
+        }
+    }
+
+    /// Receives a datagram along with the address it was sent to and the
+    /// interface it arrived on, if the socket has been asked for them with
+    /// `IP_PKTINFO` or `IPV6_PKTINFO`.
+    pub fn recv_msg(&self, buf: &mut [u8])
+                    -> io::Result<(usize, SocketAddr, Option<PacketInfo>)> {
+        let mut control = [0usize; RECV_CONTROL_WORDS];
+        debug_assert!(recv_control_space() <= mem::size_of_val(&control));
+        let (n, addr, len) = self.recv_msg_with_flags(buf, cmsg_buf(&mut control), 0)?;
+        let info = Cmsgs::new(&cmsg_buf(&mut control)[..len]).filter_map(|(level, ty, data)| {
+            match (level, ty) {
+                (c::IPPROTO_IP, c::IP_PKTINFO)
+                    if data.len() >= mem::size_of::<c::IN_PKTINFO>() => {
+                    let info = unsafe {
+                        ptr::read_unaligned(data.as_ptr() as *const c::IN_PKTINFO)
+                    };
+                    Some(PacketInfo {
+                        local_addr: IpAddr::V4(Ipv4Addr::from_inner(info.ipi_addr)),
+                        interface: info.ipi_ifindex as u32,
+                    })
+                }
+                (c::IPPROTO_IPV6, c::IPV6_PKTINFO)
+                    if data.len() >= mem::size_of::<c::IN6_PKTINFO>() => {
+                    let info = unsafe {
+                        ptr::read_unaligned(data.as_ptr() as *const c::IN6_PKTINFO)
+                    };
+                    Some(PacketInfo {
+                        local_addr: IpAddr::V6(Ipv6Addr::from_inner(info.ipi6_addr)),
+                        interface: info.ipi6_ifindex as u32,
+                    })
+                }
+                _ => None,
+            }
+        }).next();
+        Ok((n, addr, info))
+    }
+
+    pub fn set_recv_timestamps(&self, recv: bool) -> io::Result<()> {
+        let config = c::TIMESTAMPING_CONFIG {
+            Flags: if recv { c::TIMESTAMPING_FLAG_RX } else { 0 },
+            TxTimestampsBuffered: 0,
+        };
+        self.ioctl(c::SIO_TIMESTAMPING, &config, &mut ())
+    }
+
+    /// Like `recv_from`, but also returns the time the datagram was received,
+    /// if the socket has been asked for it with `SIO_TIMESTAMPING`.
+    ///
+    /// The timestamp comes as a control message, which `recvfrom` has no way
+    /// to return, so unlike `recv_from` this is built on `recv_msg_with_flags`
+    /// rather than `recv_from_with_flags`.
+    pub fn recv_from_with_timestamp(&self, buf: &mut [u8])
+                                    -> io::Result<(usize, SocketAddr, Option<SystemTime>)> {
+        let mut control = [0usize; RECV_CONTROL_WORDS];
+        debug_assert!(recv_control_space() <= mem::size_of_val(&control));
+        let (n, addr, len) = self.recv_msg_with_flags(buf, cmsg_buf(&mut control), 0)?;
+        let ticks = Cmsgs::new(&cmsg_buf(&mut control)[..len]).filter_map(|(level, ty, data)| {
+            if level == c::SOL_SOCKET && ty == c::SO_TIMESTAMP &&
+               data.len() >= mem::size_of::<u64>() {
+                Some(unsafe { ptr::read_unaligned(data.as_ptr() as *const u64) })
+            } else {
+                None
+            }
+        }).next();
+        let time = match ticks {
+            Some(ticks) => Some(qpc_to_system_time(ticks)?),
+            None => None,
+        };
+        Ok((n, addr, time))
+    }
+
+    /// Sends a datagram to `dst` from the local address and interface in
+    /// `info`, with `IP_PKTINFO` or `IPV6_PKTINFO` depending on the family of
+    /// its address.
+    pub fn send_msg(&self, buf: &[u8], dst: &SocketAddr, info: &PacketInfo)
+                    -> io::Result<usize> {
+        let mut control = [0usize; 8];
+        let len = match info.local_addr {
+            IpAddr::V4(ref addr) => {
+                write_cmsg(&mut control, c::IPPROTO_IP, c::IP_PKTINFO, &c::IN_PKTINFO {
+                    ipi_addr: *addr.as_inner(),
+                    ipi_ifindex: info.interface as c::ULONG,
+                })
+            }
+            IpAddr::V6(ref addr) => {
+                write_cmsg(&mut control, c::IPPROTO_IPV6, c::IPV6_PKTINFO, &c::IN6_PKTINFO {
+                    ipi6_addr: *addr.as_inner(),
+                    ipi6_ifindex: info.interface as c::ULONG,
+                })
+            }
+        };
+        let (dstp, dstlen) = dst.into_inner();
+        let mut wsabuf = c::WSABUF {
+            len: cmp::min(buf.len(), c::DWORD::max_value() as usize) as c::ULONG,
+            buf: buf.as_ptr() as *mut c::CHAR,
+        };
+        let mut msg = c::WSAMSG {
+            name: dstp as *mut _,
+            namelen: dstlen,
+            lpBuffers: &mut wsabuf,
+            dwBufferCount: 1,
+            Control: c::WSABUF {
+                len: len as c::ULONG,
+                buf: control.as_mut_ptr() as *mut c::CHAR,
+            },
+            dwFlags: 0,
+        };
+        let mut nsent: c::DWORD = 0;
+        cvt(unsafe {
+            c::WSASendMsg(self.0, &mut msg, 0, &mut nsent, ptr::null_mut(), None)
+        })?;
+        Ok(nsent as usize)
+    }
+
     pub fn set_timeout(&self, dur: Option<Duration>,
                        kind: c_int) -> io::Result<()> {
         let timeout = match dur {
@@ -325,6 +855,137 @@ impl Socket {
         Ok(raw != 0)
     }
 
+    pub fn set_keepalive(&self, params: Option<&KeepaliveParams>) -> io::Result<()> {
+        let params = match params {
+            Some(params) => params,
+            None => return net::setsockopt(self, c::SOL_SOCKET, c::SO_KEEPALIVE, 0 as c::BOOL),
+        };
+        // The idle time and interval are set in whole seconds. Round up so
+        // that a short, nonzero duration doesn't turn into zero.
+        let secs = |dur: Duration| -> io::Result<c::DWORD> {
+            let secs = dur.as_secs() + if dur.subsec_nanos() > 0 { 1 } else { 0 };
+            if secs == 0 {
+                return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                          "cannot set a 0 duration keepalive"));
+            }
+            if secs > c::DWORD::max_value() as u64 {
+                return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                          "keepalive duration is too long"));
+            }
+            Ok(secs as c::DWORD)
+        };
+        let idle = secs(params.idle)?;
+        let interval = secs(params.interval)?;
+
+        net::setsockopt(self, c::SOL_SOCKET, c::SO_KEEPALIVE, 1 as c::BOOL)?;
+        net::setsockopt(self, c::IPPROTO_TCP, c::TCP_KEEPALIVE, idle)?;
+        net::setsockopt(self, c::IPPROTO_TCP, c::TCP_KEEPINTVL, interval)?;
+        net::setsockopt(self, c::IPPROTO_TCP, c::TCP_KEEPCNT, params.count as c::DWORD)
+    }
+
+    pub fn keepalive(&self) -> io::Result<Option<KeepaliveParams>> {
+        let raw: c::BOOL = net::getsockopt(self, c::SOL_SOCKET, c::SO_KEEPALIVE)?;
+        if raw == 0 {
+            return Ok(None)
+        }
+        let idle: c::DWORD = net::getsockopt(self, c::IPPROTO_TCP, c::TCP_KEEPALIVE)?;
+        let interval: c::DWORD = net::getsockopt(self, c::IPPROTO_TCP, c::TCP_KEEPINTVL)?;
+        let count: c::DWORD = net::getsockopt(self, c::IPPROTO_TCP, c::TCP_KEEPCNT)?;
+        Ok(Some(KeepaliveParams {
+            idle: Duration::from_secs(idle as u64),
+            interval: Duration::from_secs(interval as u64),
+            count: count as u32,
+        }))
+    }
+
+    pub fn tcp_info(&self) -> io::Result<TcpInfo> {
+        let version: c::DWORD = 0;
+        let mut info: c::TCP_INFO_v0 = unsafe { mem::zeroed() };
+        self.ioctl(c::SIO_TCP_INFO, &version, &mut info)?;
+        // Windows doesn't track the RTT variance.
+        Ok(TcpInfo {
+            rtt: Duration::from_micros(info.RttUs as u64),
+            rtt_var: None,
+            bytes_retransmitted: info.BytesRetrans as u32,
+            cwnd: info.Cwnd as u32,
+            mss: info.Mss as u32,
+            bytes_in_flight: info.BytesInFlight as u32,
+        })
+    }
+
+    pub fn set_tos_v4(&self, dscp: Dscp) -> io::Result<()> {
+        net::setsockopt(self, c::IPPROTO_IP, c::IP_TOS, dscp.to_tos() as c_int)
+    }
+
+    pub fn tos_v4(&self) -> io::Result<Dscp> {
+        let raw: c_int = net::getsockopt(self, c::IPPROTO_IP, c::IP_TOS)?;
+        Ok(Dscp::from_tos(raw as u32))
+    }
+
+    pub fn set_tclass_v6(&self, dscp: Dscp) -> io::Result<()> {
+        net::setsockopt(self, c::IPPROTO_IPV6, c::IPV6_TCLASS, dscp.to_tos() as c_int)
+    }
+
+    pub fn tclass_v6(&self) -> io::Result<Dscp> {
+        let raw: c_int = net::getsockopt(self, c::IPPROTO_IPV6, c::IPV6_TCLASS)?;
+        Ok(Dscp::from_tos(raw as u32))
+    }
+
+    pub fn set_dont_fragment(&self, dont_fragment: bool) -> io::Result<()> {
+        net::setsockopt(self, c::IPPROTO_IP, c::IP_DONTFRAGMENT, dont_fragment as c_int)
+    }
+
+    pub fn dont_fragment(&self) -> io::Result<bool> {
+        let raw: c_int = net::getsockopt(self, c::IPPROTO_IP, c::IP_DONTFRAGMENT)?;
+        Ok(raw != 0)
+    }
+
+    pub fn path_mtu(&self) -> io::Result<u32> {
+        let raw: c_int = net::getsockopt(self, c::IPPROTO_IP, c::IP_MTU)?;
+        Ok(raw as u32)
+    }
+
+    pub fn set_dont_fragment_v6(&self, dont_fragment: bool) -> io::Result<()> {
+        net::setsockopt(self, c::IPPROTO_IPV6, c::IPV6_DONTFRAG, dont_fragment as c_int)
+    }
+
+    pub fn dont_fragment_v6(&self) -> io::Result<bool> {
+        let raw: c_int = net::getsockopt(self, c::IPPROTO_IPV6, c::IPV6_DONTFRAG)?;
+        Ok(raw != 0)
+    }
+
+    pub fn path_mtu_v6(&self) -> io::Result<u32> {
+        let raw: c_int = net::getsockopt(self, c::IPPROTO_IPV6, c::IPV6_MTU)?;
+        Ok(raw as u32)
+    }
+
+    pub fn set_linger(&self, linger: Option<Duration>) -> io::Result<()> {
+        let linger = match linger {
+            Some(dur) => {
+                // Round up so that a short, nonzero linger doesn't turn into
+                // an abortive close.
+                let secs = dur.as_secs() +
+                    if dur.subsec_nanos() > 0 { 1 } else { 0 };
+                if secs > c::USHORT::max_value() as u64 {
+                    return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                              "linger duration is too long"));
+                }
+                c::linger { l_onoff: 1, l_linger: secs as c::USHORT }
+            }
+            None => c::linger { l_onoff: 0, l_linger: 0 },
+        };
+        net::setsockopt(self, c::SOL_SOCKET, c::SO_LINGER, linger)
+    }
+
+    pub fn linger(&self) -> io::Result<Option<Duration>> {
+        let raw: c::linger = net::getsockopt(self, c::SOL_SOCKET, c::SO_LINGER)?;
+        if raw.l_onoff == 0 {
+            Ok(None)
+        } else {
+            Ok(Some(Duration::from_secs(raw.l_linger as u64)))
+        }
+    }
+
     pub fn take_error(&self) -> io::Result<Option<io::Error>> {
         let raw: c_int = net::getsockopt(self, c::SOL_SOCKET, c::SO_ERROR)?;
         if raw == 0 {
@@ -333,6 +994,171 @@ impl Socket {
             Ok(Some(io::Error::from_raw_os_error(raw as i32)))
         }
     }
+
+    /// Issues the control code `code` with `input` as the input buffer and
+    /// `output` as the output buffer. Pass `&()` or `&mut ()` for a buffer the
+    /// control code doesn't use.
+    pub fn ioctl<I, O>(&self, code: c::DWORD, input: &I, output: &mut O) -> io::Result<()> {
+        fn buf<T>(t: *const T) -> (c::LPVOID, c::DWORD) {
+            match mem::size_of::<T>() {
+                0 => (ptr::null_mut(), 0),
+                n => (t as c::LPVOID, n as c::DWORD),
+            }
+        }
+        let (inbuf, inlen) = buf(input);
+        let (outbuf, outlen) = buf(output);
+        let mut returned: c::DWORD = 0;
+        cvt(unsafe {
+            c::WSAIoctl(self.0, code, inbuf, inlen, outbuf, outlen, &mut returned,
+                        ptr::null_mut(), None)
+        })?;
+        Ok(())
+    }
+}
+
+// Describes `bufs` as an array of `WSABUF`s. Winsock reports the number of
+// bytes transferred as a `DWORD`, so the buffers are cut off once they add up
+// to more than that; a partial transfer is fine for `Read` and `Write`.
+fn wsabufs<I>(bufs: I) -> Vec<c::WSABUF>
+    where I: Iterator<Item = (*mut u8, usize)>
+{
+    let mut remaining = c::DWORD::max_value() as usize;
+    let mut wsabufs = Vec::new();
+    for (ptr, len) in bufs {
+        let n = cmp::min(len, remaining);
+        wsabufs.push(c::WSABUF { len: n as c::ULONG, buf: ptr as *mut c::CHAR });
+        remaining -= n;
+        if n < len {
+            break;
+        }
+    }
+    wsabufs
+}
+
+// Receive timestamps are `QueryPerformanceCounter` ticks, which have no fixed
+// relation to wall-clock time. They are converted against one reading of the
+// counter and of the system clock, taken back to back, by moving that reading
+// of the clock by the ticks between it and the timestamp. This is only an
+// approximation: the two readings are a few hundred nanoseconds apart at best,
+// and any adjustment of the system clock since the datagram arrived shifts the
+// result by as much.
+fn qpc_to_system_time(ticks: u64) -> io::Result<SystemTime> {
+    let mut freq: c::LARGE_INTEGER = 0;
+    let mut now: c::LARGE_INTEGER = 0;
+    let mut wall = c::FILETIME { dwLowDateTime: 0, dwHighDateTime: 0 };
+    unsafe {
+        sys::cvt(c::QueryPerformanceFrequency(&mut freq))?;
+        sys::cvt(c::QueryPerformanceCounter(&mut now))?;
+        c::GetSystemTimePreciseAsFileTime(&mut wall);
+    }
+    let wall = filetime_to_system_time(&wall);
+    let (freq, now) = (freq as u64, now as u64);
+    let to_duration = |ticks: u64| {
+        let nanos = (ticks % freq) * 1_000_000_000 / freq;
+        Duration::new(ticks / freq, nanos as u32)
+    };
+    if ticks > now {
+        Ok(wall + to_duration(ticks - now))
+    } else {
+        Ok(wall - to_duration(now - ticks))
+    }
+}
+
+// A `FILETIME` counts 100ns intervals since 1601-01-01.
+fn filetime_to_system_time(ft: &c::FILETIME) -> SystemTime {
+    const INTERVALS_PER_SEC: u64 = 10_000_000;
+    const INTERVALS_TO_UNIX_EPOCH: u64 = 11_644_473_600 * INTERVALS_PER_SEC;
+
+    let intervals = ((ft.dwHighDateTime as u64) << 32) | ft.dwLowDateTime as u64;
+    let intervals = intervals - INTERVALS_TO_UNIX_EPOCH;
+    UNIX_EPOCH + Duration::new(intervals / INTERVALS_PER_SEC,
+                               ((intervals % INTERVALS_PER_SEC) * 100) as u32)
+}
+
+// Control messages are laid out as the `WSA_CMSG_*` macros describe: each
+// header and each payload starts on a pointer-aligned boundary. Control
+// buffers are kept as `usize` arrays so that the first header is aligned too.
+fn cmsg_align(len: usize) -> usize {
+    let align = mem::align_of::<usize>();
+    (len + align - 1) & !(align - 1)
+}
+
+// The space `WSA_CMSG_SPACE` gives a control message carrying `len` bytes.
+fn cmsg_space(len: usize) -> usize {
+    cmsg_align(mem::size_of::<c::CMSGHDR>()) + cmsg_align(len)
+}
+
+// Every control message a datagram socket can be asked to deliver arrives
+// with each datagram, whichever of them the caller is after, and one that
+// doesn't fit fails the receive. So every receive makes room for all of them:
+// packet info for either family, since a dual-stack socket can have both
+// enabled, and a receive timestamp.
+const RECV_CONTROL_WORDS: usize = 32;
+
+fn recv_control_space() -> usize {
+    cmsg_space(mem::size_of::<c::IN_PKTINFO>()) +
+        cmsg_space(mem::size_of::<c::IN6_PKTINFO>()) +
+        cmsg_space(mem::size_of::<u64>())
+}
+
+fn cmsg_buf(control: &mut [usize]) -> &mut [u8] {
+    unsafe {
+        slice::from_raw_parts_mut(control.as_mut_ptr() as *mut u8,
+                                  mem::size_of_val(control))
+    }
+}
+
+// Writes a single control message carrying `data`, returning the space it
+// takes up.
+fn write_cmsg<T>(control: &mut [usize], level: c_int, ty: c_int, data: &T) -> usize {
+    let hdr_len = cmsg_align(mem::size_of::<c::CMSGHDR>());
+    let space = hdr_len + cmsg_align(mem::size_of::<T>());
+    assert!(space <= mem::size_of_val(control));
+    unsafe {
+        let base = control.as_mut_ptr() as *mut u8;
+        ptr::write(base as *mut c::CMSGHDR, c::CMSGHDR {
+            cmsg_len: hdr_len + mem::size_of::<T>(),
+            cmsg_level: level,
+            cmsg_type: ty,
+        });
+        ptr::copy_nonoverlapping(data as *const T as *const u8,
+                                 base.offset(hdr_len as isize),
+                                 mem::size_of::<T>());
+    }
+    space
+}
+
+// Iterates over the `(level, type, data)` of the control messages in a
+// buffer filled in by `WSARecvMsg`.
+struct Cmsgs<'a> {
+    buf: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Cmsgs<'a> {
+    fn new(buf: &'a [u8]) -> Cmsgs<'a> {
+        Cmsgs { buf: buf, offset: 0 }
+    }
+}
+
+impl<'a> Iterator for Cmsgs<'a> {
+    type Item = (c_int, c_int, &'a [u8]);
+
+    fn next(&mut self) -> Option<(c_int, c_int, &'a [u8])> {
+        let hdr_len = cmsg_align(mem::size_of::<c::CMSGHDR>());
+        if self.offset + mem::size_of::<c::CMSGHDR>() > self.buf.len() {
+            return None
+        }
+        let hdr = unsafe {
+            ptr::read_unaligned(self.buf[self.offset..].as_ptr() as *const c::CMSGHDR)
+        };
+        if hdr.cmsg_len < hdr_len || self.offset + hdr.cmsg_len > self.buf.len() {
+            return None
+        }
+        let data = &self.buf[self.offset + hdr_len..self.offset + hdr.cmsg_len];
+        self.offset += cmsg_align(hdr.cmsg_len);
+        Some((hdr.cmsg_level, hdr.cmsg_type, data))
+    }
 }
 
 // #[unstable(reason = "not public", issue = "0", feature = "fd_read")]
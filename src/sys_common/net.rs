@@ -16,12 +16,13 @@ use std::fmt;
 use std::io::{self, Error, ErrorKind};
 use libc::{c_int, c_void};
 use std::mem;
-use net::{SocketAddr, Shutdown, Ipv4Addr, Ipv6Addr};
+use net::{SocketAddr, Shutdown, Ipv4Addr, Ipv6Addr, KeepaliveParams, PacketInfo, UdpEvent};
+use net::{Dscp, TcpInfo};
 use std::ptr;
 use sys::net::{cvt, cvt_r, cvt_gai, Socket, init, wrlen_t};
 use sys::net::netc as c;
 use sys_common::{AsInner, FromInner, IntoInner};
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 
 #[cfg(any(target_os = "dragonfly", target_os = "freebsd",
           target_os = "ios", target_os = "macos",
@@ -201,6 +202,14 @@ impl TcpStream {
         Ok(TcpStream { inner: sock })
     }
 
+    pub fn connect_happy_eyeballs(addrs: &[SocketAddr], delay: Duration,
+                                  timeout: Option<Duration>) -> io::Result<TcpStream> {
+        init();
+
+        let addrs = interleave_families(addrs);
+        Socket::connect_race(&addrs, delay, timeout).map(|sock| TcpStream { inner: sock })
+    }
+
     pub fn socket(&self) -> &Socket { &self.inner }
 
     pub fn into_socket(self) -> Socket { self.inner }
@@ -229,6 +238,14 @@ impl TcpStream {
         self.inner.read(buf)
     }
 
+    pub fn read_vectored(&self, bufs: &mut [&mut [u8]]) -> io::Result<usize> {
+        self.inner.read_vectored(bufs)
+    }
+
+    pub fn write_vectored(&self, bufs: &[&[u8]]) -> io::Result<usize> {
+        self.inner.write_vectored(bufs)
+    }
+
     pub fn write(&self, buf: &[u8]) -> io::Result<usize> {
         let len = cmp::min(buf.len(), <wrlen_t>::max_value() as usize) as wrlen_t;
         let ret = cvt(unsafe {
@@ -268,6 +285,26 @@ impl TcpStream {
         self.inner.nodelay()
     }
 
+    pub fn set_keepalive(&self, params: Option<&KeepaliveParams>) -> io::Result<()> {
+        self.inner.set_keepalive(params)
+    }
+
+    pub fn keepalive(&self) -> io::Result<Option<KeepaliveParams>> {
+        self.inner.keepalive()
+    }
+
+    pub fn tcp_info(&self) -> io::Result<TcpInfo> {
+        self.inner.tcp_info()
+    }
+
+    pub fn set_linger(&self, linger: Option<Duration>) -> io::Result<()> {
+        self.inner.set_linger(linger)
+    }
+
+    pub fn linger(&self) -> io::Result<Option<Duration>> {
+        self.inner.linger()
+    }
+
     pub fn set_ttl(&self, ttl: u32) -> io::Result<()> {
         setsockopt(&self.inner, c::IPPROTO_IP, c::IP_TTL, ttl as c_int)
     }
@@ -277,6 +314,46 @@ impl TcpStream {
         Ok(raw as u32)
     }
 
+    pub fn set_tos_v4(&self, dscp: Dscp) -> io::Result<()> {
+        self.inner.set_tos_v4(dscp)
+    }
+
+    pub fn tos_v4(&self) -> io::Result<Dscp> {
+        self.inner.tos_v4()
+    }
+
+    pub fn set_tclass_v6(&self, dscp: Dscp) -> io::Result<()> {
+        self.inner.set_tclass_v6(dscp)
+    }
+
+    pub fn tclass_v6(&self) -> io::Result<Dscp> {
+        self.inner.tclass_v6()
+    }
+
+    pub fn set_dont_fragment(&self, dont_fragment: bool) -> io::Result<()> {
+        self.inner.set_dont_fragment(dont_fragment)
+    }
+
+    pub fn dont_fragment(&self) -> io::Result<bool> {
+        self.inner.dont_fragment()
+    }
+
+    pub fn path_mtu(&self) -> io::Result<u32> {
+        self.inner.path_mtu()
+    }
+
+    pub fn set_dont_fragment_v6(&self, dont_fragment: bool) -> io::Result<()> {
+        self.inner.set_dont_fragment_v6(dont_fragment)
+    }
+
+    pub fn dont_fragment_v6(&self) -> io::Result<bool> {
+        self.inner.dont_fragment_v6()
+    }
+
+    pub fn path_mtu_v6(&self) -> io::Result<u32> {
+        self.inner.path_mtu_v6()
+    }
+
     pub fn take_error(&self) -> io::Result<Option<io::Error>> {
         self.inner.take_error()
     }
@@ -310,6 +387,94 @@ impl fmt::Debug for TcpStream {
     }
 }
 
+////////////////////////////////////////////////////////////////////////////////
+// Options set before bind
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Clone, Debug, Default)]
+pub struct BindOpts {
+    pub reuse_address: bool,
+    pub exclusive_address_use: bool,
+    pub reuse_port: bool,
+    pub only_v6: Option<bool>,
+    pub send_buffer_size: Option<usize>,
+    pub recv_buffer_size: Option<usize>,
+}
+
+impl BindOpts {
+    fn socket(&self, addr: &SocketAddr, ty: c_int) -> io::Result<Socket> {
+        init();
+
+        // Windows has no SO_REUSEPORT. Its SO_REUSEADDR does let several
+        // sockets bind the same address and port, but without the load
+        // balancing SO_REUSEPORT promises: any process can take over the
+        // port, so it isn't quietly substituted.
+        if self.reuse_port {
+            return Err(Error::new(ErrorKind::InvalidInput,
+                                  "SO_REUSEPORT is not supported on this platform"));
+        }
+        let send_buffer_size = match self.send_buffer_size {
+            Some(size) => Some(buffer_size(size)?),
+            None => None,
+        };
+        let recv_buffer_size = match self.recv_buffer_size {
+            Some(size) => Some(buffer_size(size)?),
+            None => None,
+        };
+
+        let sock = Socket::new(addr, ty)?;
+
+        if self.reuse_address {
+            setsockopt(&sock, c::SOL_SOCKET, c::SO_REUSEADDR, 1 as c_int)?;
+        }
+        if self.exclusive_address_use {
+            setsockopt(&sock, c::SOL_SOCKET, c::SO_EXCLUSIVEADDRUSE, 1 as c_int)?;
+        }
+        if let Some(only_v6) = self.only_v6 {
+            setsockopt(&sock, c::IPPROTO_IPV6, c::IPV6_V6ONLY, only_v6 as c_int)?;
+        }
+        if let Some(size) = send_buffer_size {
+            setsockopt(&sock, c::SOL_SOCKET, c::SO_SNDBUF, size)?;
+        }
+        if let Some(size) = recv_buffer_size {
+            setsockopt(&sock, c::SOL_SOCKET, c::SO_RCVBUF, size)?;
+        }
+
+        let (addrp, len) = addr.into_inner();
+        cvt(unsafe { c::bind(*sock.as_inner(), addrp, len as _) })?;
+        Ok(sock)
+    }
+}
+
+fn buffer_size(size: usize) -> io::Result<c_int> {
+    if size > c_int::max_value() as usize {
+        return Err(Error::new(ErrorKind::InvalidInput, "buffer size is too large"));
+    }
+    Ok(size as c_int)
+}
+
+// Orders `addrs` as RFC 8305 section 4 asks: alternating between address
+// families, starting with the family of the first address, and otherwise
+// keeping the order the addresses were resolved in.
+fn interleave_families(addrs: &[SocketAddr]) -> Vec<SocketAddr> {
+    let first_v4 = match addrs.first() {
+        Some(addr) => addr.is_ipv4(),
+        None => return Vec::new(),
+    };
+    let (mut first, mut second): (Vec<_>, Vec<_>) =
+        addrs.iter().partition(|addr| addr.is_ipv4() == first_v4);
+    first.reverse();
+    second.reverse();
+
+    let mut interleaved = Vec::with_capacity(addrs.len());
+    loop {
+        match (first.pop(), second.pop()) {
+            (None, None) => return interleaved,
+            (a, b) => interleaved.extend(a.into_iter().chain(b).cloned()),
+        }
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // TCP listeners
 ////////////////////////////////////////////////////////////////////////////////
@@ -341,6 +506,13 @@ impl TcpListener {
         Ok(TcpListener { inner: sock })
     }
 
+    pub fn bind_opts(addr: &SocketAddr, opts: &BindOpts,
+                     backlog: c_int) -> io::Result<TcpListener> {
+        let sock = opts.socket(addr, c::SOCK_STREAM)?;
+        cvt(unsafe { c::listen(*sock.as_inner(), backlog) })?;
+        Ok(TcpListener { inner: sock })
+    }
+
     pub fn socket(&self) -> &Socket { &self.inner }
 
     pub fn into_socket(self) -> Socket { self.inner }
@@ -373,6 +545,22 @@ impl TcpListener {
         Ok(raw as u32)
     }
 
+    pub fn set_tos_v4(&self, dscp: Dscp) -> io::Result<()> {
+        self.inner.set_tos_v4(dscp)
+    }
+
+    pub fn tos_v4(&self) -> io::Result<Dscp> {
+        self.inner.tos_v4()
+    }
+
+    pub fn set_tclass_v6(&self, dscp: Dscp) -> io::Result<()> {
+        self.inner.set_tclass_v6(dscp)
+    }
+
+    pub fn tclass_v6(&self) -> io::Result<Dscp> {
+        self.inner.tclass_v6()
+    }
+
     pub fn set_only_v6(&self, only_v6: bool) -> io::Result<()> {
         setsockopt(&self.inner, c::IPPROTO_IPV6, c::IPV6_V6ONLY, only_v6 as c_int)
     }
@@ -426,7 +614,11 @@ impl UdpSocket {
         let sock = Socket::new(addr, c::SOCK_DGRAM)?;
         let (addrp, len) = addr.into_inner();
         cvt(unsafe { c::bind(*sock.as_inner(), addrp, len as _) })?;
-        Ok(UdpSocket { inner: sock })
+        Ok(UdpSocket::from_inner(sock))
+    }
+
+    pub fn bind_opts(addr: &SocketAddr, opts: &BindOpts) -> io::Result<UdpSocket> {
+        opts.socket(addr, c::SOCK_DGRAM).map(UdpSocket::from_inner)
     }
 
     pub fn socket(&self) -> &Socket { &self.inner }
@@ -447,6 +639,69 @@ impl UdpSocket {
         self.inner.peek_from(buf)
     }
 
+    pub fn set_recv_timestamps(&self, recv: bool) -> io::Result<()> {
+        self.inner.set_recv_timestamps(recv)
+    }
+
+    pub fn recv_from_with_timestamp(&self, buf: &mut [u8])
+                                    -> io::Result<(usize, SocketAddr, Option<SystemTime>)> {
+        self.inner.recv_from_with_timestamp(buf)
+    }
+
+    pub fn recv_event(&self, buf: &mut [u8]) -> io::Result<UdpEvent> {
+        self.inner.recv_event(buf)
+    }
+
+    pub fn set_connreset(&self, report: bool) -> io::Result<()> {
+        self.inner.set_connreset(report)
+    }
+
+    pub fn recv_batch(&self, bufs: &mut [&mut [u8]])
+                      -> io::Result<Vec<(usize, SocketAddr)>> {
+        self.inner.recv_batch(bufs)
+    }
+
+    pub fn send_batch(&self, bufs: &[&[u8]], addrs: &[SocketAddr]) -> io::Result<usize> {
+        self.inner.send_batch(bufs, addrs)
+    }
+
+    pub fn recv_msg(&self, buf: &mut [u8])
+                    -> io::Result<(usize, SocketAddr, Option<PacketInfo>)> {
+        self.inner.recv_msg(buf)
+    }
+
+    pub fn send_msg(&self, buf: &[u8], dst: &SocketAddr, info: &PacketInfo)
+                    -> io::Result<usize> {
+        self.inner.send_msg(buf, dst, info)
+    }
+
+    pub fn set_recv_packet_info(&self, recv: bool) -> io::Result<()> {
+        match self.socket_addr()? {
+            SocketAddr::V4(..) => {
+                setsockopt(&self.inner, c::IPPROTO_IP, c::IP_PKTINFO, recv as c_int)
+            }
+            SocketAddr::V6(..) => {
+                setsockopt(&self.inner, c::IPPROTO_IPV6, c::IPV6_PKTINFO, recv as c_int)?;
+                // A dual-stack socket reports the packet info of datagrams
+                // sent to an IPv4 address under IP_PKTINFO.
+                let only_v6: c_int = getsockopt(&self.inner, c::IPPROTO_IPV6,
+                                                c::IPV6_V6ONLY)?;
+                if only_v6 == 0 {
+                    setsockopt(&self.inner, c::IPPROTO_IP, c::IP_PKTINFO, recv as c_int)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    pub fn recv_packet_info(&self) -> io::Result<bool> {
+        let raw: c_int = match self.socket_addr()? {
+            SocketAddr::V4(..) => getsockopt(&self.inner, c::IPPROTO_IP, c::IP_PKTINFO)?,
+            SocketAddr::V6(..) => getsockopt(&self.inner, c::IPPROTO_IPV6, c::IPV6_PKTINFO)?,
+        };
+        Ok(raw != 0)
+    }
+
     pub fn send_to(&self, buf: &[u8], dst: &SocketAddr) -> io::Result<usize> {
         let len = cmp::min(buf.len(), <wrlen_t>::max_value() as usize) as wrlen_t;
         let (dstp, dstlen) = dst.into_inner();
@@ -514,6 +769,33 @@ impl UdpSocket {
         Ok(raw != 0)
     }
 
+    pub fn set_multicast_hops_v6(&self, multicast_hops_v6: u32) -> io::Result<()> {
+        setsockopt(&self.inner, c::IPPROTO_IPV6, c::IPV6_MULTICAST_HOPS, multicast_hops_v6 as c_int)
+    }
+
+    pub fn multicast_hops_v6(&self) -> io::Result<u32> {
+        let raw: c_int = getsockopt(&self.inner, c::IPPROTO_IPV6, c::IPV6_MULTICAST_HOPS)?;
+        Ok(raw as u32)
+    }
+
+    pub fn set_multicast_if_v4(&self, interface: &Ipv4Addr) -> io::Result<()> {
+        setsockopt(&self.inner, c::IPPROTO_IP, c::IP_MULTICAST_IF, *interface.as_inner())
+    }
+
+    pub fn multicast_if_v4(&self) -> io::Result<Ipv4Addr> {
+        let raw: c::in_addr = getsockopt(&self.inner, c::IPPROTO_IP, c::IP_MULTICAST_IF)?;
+        Ok(Ipv4Addr::from_inner(raw))
+    }
+
+    pub fn set_multicast_if_v6(&self, interface: u32) -> io::Result<()> {
+        setsockopt(&self.inner, c::IPPROTO_IPV6, c::IPV6_MULTICAST_IF, interface as c_int)
+    }
+
+    pub fn multicast_if_v6(&self) -> io::Result<u32> {
+        let raw: c_int = getsockopt(&self.inner, c::IPPROTO_IPV6, c::IPV6_MULTICAST_IF)?;
+        Ok(raw as u32)
+    }
+
     pub fn join_multicast_v4(&self, multiaddr: &Ipv4Addr, interface: &Ipv4Addr)
                          -> io::Result<()> {
         let mreq = c::ip_mreq {
@@ -550,6 +832,26 @@ impl UdpSocket {
         setsockopt(&self.inner, c::IPPROTO_IPV6, IPV6_DROP_MEMBERSHIP, mreq)
     }
 
+    pub fn join_ssm_v4(&self, multiaddr: &Ipv4Addr, source: &Ipv4Addr, interface: &Ipv4Addr)
+                       -> io::Result<()> {
+        let mreq = c::ip_mreq_source {
+            imr_multiaddr: *multiaddr.as_inner(),
+            imr_sourceaddr: *source.as_inner(),
+            imr_interface: *interface.as_inner(),
+        };
+        setsockopt(&self.inner, c::IPPROTO_IP, c::IP_ADD_SOURCE_MEMBERSHIP, mreq)
+    }
+
+    pub fn leave_ssm_v4(&self, multiaddr: &Ipv4Addr, source: &Ipv4Addr, interface: &Ipv4Addr)
+                        -> io::Result<()> {
+        let mreq = c::ip_mreq_source {
+            imr_multiaddr: *multiaddr.as_inner(),
+            imr_sourceaddr: *source.as_inner(),
+            imr_interface: *interface.as_inner(),
+        };
+        setsockopt(&self.inner, c::IPPROTO_IP, c::IP_DROP_SOURCE_MEMBERSHIP, mreq)
+    }
+
     pub fn set_ttl(&self, ttl: u32) -> io::Result<()> {
         setsockopt(&self.inner, c::IPPROTO_IP, c::IP_TTL, ttl as c_int)
     }
@@ -559,6 +861,46 @@ impl UdpSocket {
         Ok(raw as u32)
     }
 
+    pub fn set_tos_v4(&self, dscp: Dscp) -> io::Result<()> {
+        self.inner.set_tos_v4(dscp)
+    }
+
+    pub fn tos_v4(&self) -> io::Result<Dscp> {
+        self.inner.tos_v4()
+    }
+
+    pub fn set_tclass_v6(&self, dscp: Dscp) -> io::Result<()> {
+        self.inner.set_tclass_v6(dscp)
+    }
+
+    pub fn tclass_v6(&self) -> io::Result<Dscp> {
+        self.inner.tclass_v6()
+    }
+
+    pub fn set_dont_fragment(&self, dont_fragment: bool) -> io::Result<()> {
+        self.inner.set_dont_fragment(dont_fragment)
+    }
+
+    pub fn dont_fragment(&self) -> io::Result<bool> {
+        self.inner.dont_fragment()
+    }
+
+    pub fn path_mtu(&self) -> io::Result<u32> {
+        self.inner.path_mtu()
+    }
+
+    pub fn set_dont_fragment_v6(&self, dont_fragment: bool) -> io::Result<()> {
+        self.inner.set_dont_fragment_v6(dont_fragment)
+    }
+
+    pub fn dont_fragment_v6(&self) -> io::Result<bool> {
+        self.inner.dont_fragment_v6()
+    }
+
+    pub fn path_mtu_v6(&self) -> io::Result<u32> {
+        self.inner.path_mtu_v6()
+    }
+
     pub fn take_error(&self) -> io::Result<Option<io::Error>> {
         self.inner.take_error()
     }
@@ -627,4 +969,20 @@ mod tests {
         let _na = lh.map(|sa| *addrs.entry(sa).or_insert(0) += 1).count();
         assert!(addrs.values().filter(|&&v| v > 1).count() == 0);
     }
+
+    #[test]
+    fn interleave_families() {
+        let v6a: SocketAddr = "[::1]:1".parse().unwrap();
+        let v6b: SocketAddr = "[::1]:2".parse().unwrap();
+        let v6c: SocketAddr = "[::1]:3".parse().unwrap();
+        let v4a: SocketAddr = "127.0.0.1:1".parse().unwrap();
+        let v4b: SocketAddr = "127.0.0.1:2".parse().unwrap();
+
+        assert_eq!(super::interleave_families(&[v6a, v6b, v6c, v4a, v4b]),
+                   vec![v6a, v4a, v6b, v4b, v6c]);
+        assert_eq!(super::interleave_families(&[v4a, v6a, v6b, v4b]),
+                   vec![v4a, v6a, v4b, v6b]);
+        assert_eq!(super::interleave_families(&[v6a, v6b]), vec![v6a, v6b]);
+        assert_eq!(super::interleave_families(&[]), Vec::<SocketAddr>::new());
+    }
 }